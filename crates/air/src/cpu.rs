@@ -1,6 +1,114 @@
 //! CPU AIR constraints for RV32IM.
 
-use zp1_primitives::M31;
+use zp1_primitives::{M31, QM31};
+
+/// A field element known to be boolean (0 or 1), carrying its own
+/// booleanity obligation once instead of every call site hand-rolling
+/// `bit*(bit-1)=0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Boolean(M31);
+
+impl Boolean {
+    /// Wrap a raw value. Booleanity isn't assumed — call
+    /// [`Boolean::booleanity_constraint`] to get the constraint proving it.
+    #[inline]
+    pub fn new(value: M31) -> Self {
+        Boolean(value)
+    }
+
+    /// The underlying field value.
+    #[inline]
+    pub fn value(self) -> M31 {
+        self.0
+    }
+
+    /// `b*(b-1) = 0`: the constraint this value must satisfy to actually
+    /// be boolean.
+    #[inline]
+    pub fn booleanity_constraint(self) -> M31 {
+        self.0 * (self.0 - M31::ONE)
+    }
+
+    #[inline]
+    pub fn and(self, other: Boolean) -> Boolean {
+        Boolean(self.0 * other.0)
+    }
+
+    #[inline]
+    pub fn or(self, other: Boolean) -> Boolean {
+        Boolean(self.0 + other.0 - self.0 * other.0)
+    }
+
+    #[inline]
+    pub fn xor(self, other: Boolean) -> Boolean {
+        Boolean(self.0 + other.0 - M31::new(2) * self.0 * other.0)
+    }
+
+    #[inline]
+    pub fn not(self) -> Boolean {
+        Boolean(M31::ONE - self.0)
+    }
+}
+
+/// How many bits of headroom [`MultiEq`] packs into a single field
+/// element before flushing, kept comfortably under M31's 31-bit modulus
+/// so a run of packed claims can never wrap around and falsely cancel out.
+const MULTIEQ_CAPACITY_BITS: u32 = 24;
+
+/// Packs a run of small (`< 2^bits`) equality claims `lhs = rhs` into a
+/// single field-element equality, the way bellman-style circuits'
+/// `MultiEq` gadget batches many boolean/bit equalities to save
+/// constraints: each claim contributes `(lhs - rhs) * 2^offset` to a
+/// running sum, `offset` advances by the claim's bit width, and once the
+/// next claim would overflow [`MULTIEQ_CAPACITY_BITS`] the accumulated sum
+/// is flushed into one "must be zero" constraint before `offset` resets.
+///
+/// Soundness relies on each pushed claim's `lhs`/`rhs` already being
+/// individually bounded to `< 2^bits` (e.g. [`Boolean`] values, or values
+/// bit-decomposed elsewhere) — the packed equality is then exactly as
+/// strong as checking every claim separately, since distinct claims can
+/// never carry into each other's bit range.
+#[derive(Debug, Default)]
+pub struct MultiEq {
+    sum: M31,
+    offset: u32,
+    constraints: Vec<M31>,
+}
+
+impl MultiEq {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push a claim that `lhs == rhs`, where both are known to be
+    /// `< 2^bits`.
+    pub fn push(&mut self, lhs: M31, rhs: M31, bits: u32) {
+        if self.offset + bits > MULTIEQ_CAPACITY_BITS {
+            self.flush();
+        }
+
+        let weight = M31::new(1 << self.offset);
+        self.sum = self.sum + (lhs - rhs) * weight;
+        self.offset += bits;
+    }
+
+    /// Flush any pending packed claims into a single constraint, leaving
+    /// the accumulator ready for the next group.
+    pub fn flush(&mut self) {
+        if self.offset > 0 {
+            self.constraints.push(self.sum);
+            self.sum = M31::ZERO;
+            self.offset = 0;
+        }
+    }
+
+    /// Consume the accumulator, flushing any pending group, and return
+    /// every packed constraint produced.
+    pub fn finish(mut self) -> Vec<M31> {
+        self.flush();
+        self.constraints
+    }
+}
 
 /// CPU AIR constraint evaluator.
 ///
@@ -81,647 +189,3469 @@ impl CpuAir {
 
     /// Evaluate bit decomposition constraint.
     /// Ensures that:
-    /// 1. Each bit is binary (bit * (bit - 1) = 0)
-    /// 2. Bits reconstruct the original 32-bit value
+    /// 1. Each bit is binary (via [`Boolean::booleanity_constraint`])
+    /// 2. Bits reconstruct the original 32-bit value (packed via
+    ///    [`MultiEq`] instead of two hand-rolled constraints)
     ///
     /// # Arguments
     /// * `value_lo` - Lower 16-bit limb of the value
-    /// * `value_hi` - Upper 16-bit limb of the value  
+    /// * `value_hi` - Upper 16-bit limb of the value
     /// * `bits` - Array of 32 individual bit values
     ///
     /// # Returns
-    /// Vector of 34 constraints (32 bit constraints + 2 reconstruction constraints)
+    /// Vector of 34 constraints (32 booleanity constraints + the packed
+    /// reconstruction constraints `MultiEq` produces for the two limbs)
     pub fn bit_decomposition_constraints(
         value_lo: M31,
         value_hi: M31,
         bits: &[M31; 32],
     ) -> Vec<M31> {
         let mut constraints = Vec::with_capacity(34);
-        
-        // Constraint: each bit must be 0 or 1
-        // bit * (bit - 1) = 0
+
         for &bit in bits {
-            constraints.push(bit * (bit - M31::ONE));
+            constraints.push(Boolean::new(bit).booleanity_constraint());
         }
-        
-        // Constraint: bits must reconstruct the value
-        // value = bits[0] + 2*bits[1] + 4*bits[2] + ... + 2^31*bits[31]
+
+        // bits must reconstruct the value:
+        // value = bits[0] + 2*bits[1] + 4*bits[2] + ... + 2^15*bits[15]
         let mut recon_lo = M31::ZERO;
         let mut recon_hi = M31::ZERO;
         let mut power = M31::ONE;
-        
+
         for i in 0..32 {
             if i < 16 {
                 recon_lo = recon_lo + bits[i] * power;
             } else {
                 recon_hi = recon_hi + bits[i] * power;
             }
-            
+
             // Update power: multiply by 2 (mod p)
             power = power + power;
-            
+
             // After bit 15, reset power for high limb
             if i == 15 {
                 power = M31::ONE;
             }
         }
-        
-        // Reconstruction constraints
-        constraints.push(value_lo - recon_lo);
-        constraints.push(value_hi - recon_hi);
-        
+
+        let mut eq = MultiEq::new();
+        eq.push(value_lo, recon_lo, 16);
+        eq.push(value_hi, recon_hi, 16);
+        constraints.extend(eq.finish());
+
         constraints
     }
 
     /// Evaluate AND constraint for bitwise operations.
-    /// result[i] = a[i] AND b[i] = a[i] * b[i]
+    /// result[i] = a[i] AND b[i], packed via [`MultiEq`] instead of
+    /// emitting one constraint per bit.
     ///
     /// # Returns
-    /// Vector of 32 constraints (one per bit)
-    #[inline]
+    /// A small number of packed constraints (one per
+    /// [`MULTIEQ_CAPACITY_BITS`] bits, instead of 32).
     pub fn bitwise_and_constraints(
         bits_a: &[M31; 32],
         bits_b: &[M31; 32],
         bits_result: &[M31; 32],
     ) -> Vec<M31> {
-        let mut constraints = Vec::with_capacity(32);
+        let mut eq = MultiEq::new();
         for i in 0..32 {
-            // result[i] = a[i] * b[i]
-            constraints.push(bits_result[i] - bits_a[i] * bits_b[i]);
+            let computed = Boolean::new(bits_a[i]).and(Boolean::new(bits_b[i])).value();
+            eq.push(bits_result[i], computed, 1);
         }
-        constraints
+        eq.finish()
     }
 
     /// Evaluate OR constraint for bitwise operations.
-    /// result[i] = a[i] OR b[i] = a[i] + b[i] - a[i]*b[i]
+    /// result[i] = a[i] OR b[i], packed via [`MultiEq`] instead of
+    /// emitting one constraint per bit.
     ///
     /// # Returns
-    /// Vector of 32 constraints (one per bit)
-    #[inline]
+    /// A small number of packed constraints (one per
+    /// [`MULTIEQ_CAPACITY_BITS`] bits, instead of 32).
     pub fn bitwise_or_constraints(
         bits_a: &[M31; 32],
         bits_b: &[M31; 32],
         bits_result: &[M31; 32],
     ) -> Vec<M31> {
-        let mut constraints = Vec::with_capacity(32);
+        let mut eq = MultiEq::new();
         for i in 0..32 {
-            // result[i] = a[i] + b[i] - a[i]*b[i]
-            constraints.push(bits_result[i] - (bits_a[i] + bits_b[i] - bits_a[i] * bits_b[i]));
+            let computed = Boolean::new(bits_a[i]).or(Boolean::new(bits_b[i])).value();
+            eq.push(bits_result[i], computed, 1);
         }
-        constraints
+        eq.finish()
     }
 
     /// Evaluate XOR constraint for bitwise operations.
-    /// result[i] = a[i] XOR b[i] = a[i] + b[i] - 2*a[i]*b[i]
+    /// result[i] = a[i] XOR b[i], packed via [`MultiEq`] instead of
+    /// emitting one constraint per bit.
     ///
     /// # Returns
-    /// Vector of 32 constraints (one per bit)
-    #[inline]
+    /// A small number of packed constraints (one per
+    /// [`MULTIEQ_CAPACITY_BITS`] bits, instead of 32).
     pub fn bitwise_xor_constraints(
         bits_a: &[M31; 32],
         bits_b: &[M31; 32],
         bits_result: &[M31; 32],
     ) -> Vec<M31> {
-        let mut constraints = Vec::with_capacity(32);
-        let two = M31::new(2);
+        let mut eq = MultiEq::new();
         for i in 0..32 {
-            // result[i] = a[i] + b[i] - 2*a[i]*b[i]
-            constraints.push(bits_result[i] - (bits_a[i] + bits_b[i] - two * bits_a[i] * bits_b[i]));
+            let computed = Boolean::new(bits_a[i]).xor(Boolean::new(bits_b[i])).value();
+            eq.push(bits_result[i], computed, 1);
+        }
+        eq.finish()
+    }
+
+    /// Evaluate the binary-selector constraints a variable shift amount is
+    /// bound to: a barrel shifter with one selector `s_k` per possible
+    /// shift `k` in `0..32`, rather than reading the concrete shift amount
+    /// out of the witness in Rust (which lets a malicious prover claim any
+    /// shift it likes, since nothing in the AIR ties the claimed result to
+    /// it).
+    ///
+    /// # Arguments
+    /// * `selectors` - `s_0..s_31`, exactly one of which must be 1
+    /// * `shift_amount` - the shift amount the selectors must reconstruct
+    /// * `pow2` - the `1 << shift_amount` witness the SLL/SRL/SRA
+    ///   constraints below consume
+    ///
+    /// # Returns
+    /// 32 booleanity constraints (`s_k*(s_k-1)=0`), plus the selector-sum
+    /// constraint (`sum_k s_k = 1`), the `shift_amount` binding
+    /// (`shift_amount = sum_k k*s_k`), and the `pow2` binding
+    /// (`pow2 = sum_k s_k*2^k`) — 35 constraints in total. A shift amount
+    /// of 32 or more has no selector to set, so it's simply unrepresentable
+    /// rather than needing an explicit range check.
+    pub fn shift_selector_constraints(
+        selectors: &[M31; 32],
+        shift_amount: M31,
+        pow2: M31,
+    ) -> Vec<M31> {
+        let mut constraints = Vec::with_capacity(32 + 3);
+
+        let mut selector_sum = M31::ZERO;
+        let mut weighted_shift = M31::ZERO;
+        let mut weighted_pow2 = M31::ZERO;
+        let mut power = M31::ONE;
+
+        for (k, &s_k) in selectors.iter().enumerate() {
+            constraints.push(s_k * (s_k - M31::ONE));
+            selector_sum = selector_sum + s_k;
+            weighted_shift = weighted_shift + M31::new(k as u32) * s_k;
+            weighted_pow2 = weighted_pow2 + power * s_k;
+            power = power + power;
         }
+
+        constraints.push(selector_sum - M31::ONE);
+        constraints.push(shift_amount - weighted_shift);
+        constraints.push(pow2 - weighted_pow2);
+
         constraints
     }
 
+    /// Build the `s_0..s_31` selector witnesses for a concrete shift amount
+    /// (taken `mod 32`, matching the RISC-V shift semantics), for use by
+    /// witness generation and by tests of the shift constraints.
+    pub fn shift_selectors(shift_amount: u32) -> [M31; 32] {
+        let mut selectors = [M31::ZERO; 32];
+        selectors[(shift_amount % 32) as usize] = M31::ONE;
+        selectors
+    }
+
     /// Evaluate SLL (Shift Left Logical) constraint.
-    /// result = value << (shift_amount % 32)
-    /// 
+    /// result = value << shift, where `shift` is the amount `selectors`
+    /// (see [`CpuAir::shift_selector_constraints`]) is bound to.
+    ///
     /// # Arguments
     /// * `bits_value` - Bit decomposition of input value
     /// * `bits_result` - Bit decomposition of result
-    /// * `shift_amount` - Number of positions to shift (0-31)
+    /// * `selectors` - Binary shift-amount selectors `s_0..s_31`
     ///
     /// # Returns
-    /// Vector of 32 constraints enforcing correct shift
+    /// Vector of 32 constraints enforcing correct shift, valid for any
+    /// selector assignment (not just ones witness generation produced).
     pub fn shift_left_logical_constraints(
         bits_value: &[M31; 32],
         bits_result: &[M31; 32],
-        shift_amount: M31,
+        selectors: &[M31; 32],
     ) -> Vec<M31> {
         let mut constraints = Vec::with_capacity(32);
-        
-        // For each possible shift amount (0-31), we need to check:
-        // If shift_amount == k, then result[i] = value[i-k] for i >= k, else 0
-        // We use selector pattern: is_shift_k * (result[i] - expected[i]) = 0
-        
-        // Convert shift_amount to u32 for computation
-        // Note: In real implementation, shift_amount should be range-checked [0, 31]
-        let shift_val = shift_amount.value() % 32;
-        
+
+        // result[i] = sum_{k <= i} s_k * value[i - k]: whichever selector
+        // is set picks out the one term that matters, and bits shifted in
+        // from the right (no k <= i contributes for i < k) are implicitly 0.
         for i in 0..32 {
-            if i < shift_val as usize {
-                // Bits shifted in from right are 0
-                constraints.push(bits_result[i]);
-            } else {
-                // Bit i of result comes from bit (i - shift) of input
-                let src_idx = i - shift_val as usize;
-                constraints.push(bits_result[i] - bits_value[src_idx]);
+            let mut expected = M31::ZERO;
+            for (k, &s_k) in selectors.iter().enumerate().take(i + 1) {
+                expected = expected + s_k * bits_value[i - k];
             }
+            constraints.push(bits_result[i] - expected);
         }
-        
+
         constraints
     }
 
     /// Evaluate SRL (Shift Right Logical) constraint.
-    /// result = value >> (shift_amount % 32)
-    /// Zero-extends from left.
+    /// result = value >> shift, where `shift` is the amount `selectors`
+    /// is bound to. Zero-extends from the left.
     ///
     /// # Returns
-    /// Vector of 32 constraints enforcing correct shift
+    /// Vector of 32 constraints enforcing correct shift, valid for any
+    /// selector assignment.
     pub fn shift_right_logical_constraints(
         bits_value: &[M31; 32],
         bits_result: &[M31; 32],
-        shift_amount: M31,
+        selectors: &[M31; 32],
     ) -> Vec<M31> {
         let mut constraints = Vec::with_capacity(32);
-        
-        let shift_val = shift_amount.value() % 32;
-        
+
+        // result[i] = sum_k s_k * value[i + k] for i + k < 32; bits shifted
+        // in from the left (i + k >= 32 for the selected k) are 0.
         for i in 0..32 {
-            let src_idx = i + shift_val as usize;
-            if src_idx >= 32 {
-                // Bits shifted in from left are 0
-                constraints.push(bits_result[i]);
-            } else {
-                // Bit i of result comes from bit (i + shift) of input
-                constraints.push(bits_result[i] - bits_value[src_idx]);
+            let mut expected = M31::ZERO;
+            for (k, &s_k) in selectors.iter().enumerate() {
+                if i + k < 32 {
+                    expected = expected + s_k * bits_value[i + k];
+                }
             }
+            constraints.push(bits_result[i] - expected);
         }
-        
+
         constraints
     }
 
     /// Evaluate SRA (Shift Right Arithmetic) constraint.
-    /// result = value >> (shift_amount % 32)
-    /// Sign-extends from left (replicates bit 31).
+    /// result = value >> shift, where `shift` is the amount `selectors`
+    /// is bound to. Sign-extends from the left (replicates bit 31).
     ///
     /// # Returns
-    /// Vector of 32 constraints enforcing correct shift
+    /// Vector of 32 constraints enforcing correct shift, valid for any
+    /// selector assignment.
     pub fn shift_right_arithmetic_constraints(
         bits_value: &[M31; 32],
         bits_result: &[M31; 32],
-        shift_amount: M31,
+        selectors: &[M31; 32],
     ) -> Vec<M31> {
         let mut constraints = Vec::with_capacity(32);
-        
-        let shift_val = shift_amount.value() % 32;
-        let sign_bit = bits_value[31]; // MSB is sign bit
-        
+        let sign_bit = bits_value[31];
+
+        // Same as SRL, except the k for which i + k >= 32 contributes the
+        // sign bit instead of being dropped.
         for i in 0..32 {
-            let src_idx = i + shift_val as usize;
-            if src_idx >= 32 {
-                // Bits shifted in from left are sign bit
-                constraints.push(bits_result[i] - sign_bit);
-            } else {
-                // Bit i of result comes from bit (i + shift) of input
-                constraints.push(bits_result[i] - bits_value[src_idx]);
+            let mut expected = M31::ZERO;
+            for (k, &s_k) in selectors.iter().enumerate() {
+                if i + k < 32 {
+                    expected = expected + s_k * bits_value[i + k];
+                } else {
+                    expected = expected + s_k * sign_bit;
+                }
             }
+            constraints.push(bits_result[i] - expected);
         }
-        
+
         constraints
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Evaluate the limb-decomposed unsigned 64-bit product of two 32-bit
+    /// operands (each given as 16-bit limb pairs): the four partial
+    /// products `rs1_lo*rs2_lo`, `rs1_lo*rs2_hi`, `rs1_hi*rs2_lo`,
+    /// `rs1_hi*rs2_hi` combined with carry witnesses into the product's
+    /// own `prod_0..prod_3` 16-bit limbs, low to high — the same
+    /// carry-threading `add_constraint` uses between a 32-bit value's two
+    /// halves, just with more limb boundaries to cross. MUL, MULH, MULHU,
+    /// MULHSU, and the DIV/REM identity below all build on this.
+    ///
+    /// # Returns
+    /// 4 constraints, one per product limb.
+    #[allow(clippy::too_many_arguments)]
+    pub fn mul_product_constraints(
+        rs1_lo: M31,
+        rs1_hi: M31,
+        rs2_lo: M31,
+        rs2_hi: M31,
+        prod_0: M31,
+        prod_1: M31,
+        prod_2: M31,
+        prod_3: M31,
+        carry_0: M31,
+        carry_1: M31,
+        carry_2: M31,
+    ) -> Vec<M31> {
+        let two_16 = M31::new(1 << 16);
 
-    /// Helper to convert u32 to bit array
-    fn u32_to_bits(value: u32) -> [M31; 32] {
-        let mut bits = [M31::ZERO; 32];
-        for i in 0..32 {
-            bits[i] = if (value >> i) & 1 == 1 {
-                M31::ONE
-            } else {
-                M31::ZERO
-            };
-        }
-        bits
-    }
+        let c0 = prod_0 - rs1_lo * rs2_lo + carry_0 * two_16;
+        let c1 = prod_1 - (rs1_lo * rs2_hi + rs1_hi * rs2_lo) - carry_0 + carry_1 * two_16;
+        let c2 = prod_2 - rs1_hi * rs2_hi - carry_1 + carry_2 * two_16;
+        let c3 = prod_3 - carry_2;
 
-    /// Helper to split u32 into limbs
-    fn u32_to_limbs(value: u32) -> (M31, M31) {
-        let lo = value & 0xFFFF;
-        let hi = value >> 16;
-        (M31::new(lo), M31::new(hi))
+        vec![c0, c1, c2, c3]
     }
 
-    #[test]
-    fn test_bit_decomposition_valid() {
-        // Test with value 0x12345678
-        let value = 0x12345678u32;
-        let (lo, hi) = u32_to_limbs(value);
-        let bits = u32_to_bits(value);
-
-        let constraints = CpuAir::bit_decomposition_constraints(lo, hi, &bits);
-        
-        // All 34 constraints should be satisfied (= 0)
-        assert_eq!(constraints.len(), 34);
-        for (i, constraint) in constraints.iter().enumerate() {
-            assert_eq!(*constraint, M31::ZERO, "Constraint {} failed", i);
-        }
+    /// Evaluate the MUL constraint: `rd = low 32 bits of rs1*rs2`,
+    /// selecting the two low limbs out of
+    /// [`CpuAir::mul_product_constraints`]'s product.
+    ///
+    /// # Returns
+    /// 2 constraints (one per limb).
+    #[inline]
+    pub fn mul_constraints(rd_val_lo: M31, rd_val_hi: M31, prod_0: M31, prod_1: M31) -> Vec<M31> {
+        vec![rd_val_lo - prod_0, rd_val_hi - prod_1]
     }
 
-    #[test]
-    fn test_bit_decomposition_all_zeros() {
-        let value = 0u32;
-        let (lo, hi) = u32_to_limbs(value);
-        let bits = u32_to_bits(value);
-
-        let constraints = CpuAir::bit_decomposition_constraints(lo, hi, &bits);
-        
-        for constraint in constraints {
-            assert_eq!(constraint, M31::ZERO);
-        }
+    /// Evaluate the MULHU constraint: `rd = high 32 bits of unsigned
+    /// rs1*rs2`. No sign correction is needed since both operands are
+    /// unsigned.
+    ///
+    /// # Returns
+    /// 2 constraints (one per limb).
+    #[inline]
+    pub fn mulhu_constraints(rd_val_lo: M31, rd_val_hi: M31, prod_2: M31, prod_3: M31) -> Vec<M31> {
+        vec![rd_val_lo - prod_2, rd_val_hi - prod_3]
     }
 
-    #[test]
-    fn test_bit_decomposition_all_ones() {
-        let value = 0xFFFFFFFFu32;
-        let (lo, hi) = u32_to_limbs(value);
-        let bits = u32_to_bits(value);
+    /// Evaluate the MULH constraint: `rd = high 32 bits of signed
+    /// rs1*rs2`.
+    ///
+    /// Two's-complement identity: interpreting a 32-bit pattern as signed
+    /// is the same as interpreting it unsigned and subtracting `2^32`
+    /// when its sign bit is set, so `signed(rs1)*signed(rs2) = rs1*rs2 -
+    /// sign1*rs2*2^32 - sign2*rs1*2^32` (dropping `sign1*sign2*2^64`,
+    /// which falls outside the 64-bit product) — only the high word needs
+    /// correcting, by `sign1*rs2_val + sign2*rs1_val`. `borrow` absorbs
+    /// the `2^32` the correction can pull from when it underflows, the
+    /// same role `add_constraint`'s `carry` plays in the other direction.
+    ///
+    /// # Arguments
+    /// * `sign1`, `sign2` - bit 31 of `rs1_val`, `rs2_val`
+    /// * `rs1_val`, `rs2_val` - the full (unsigned-pattern) 32-bit operands
+    ///
+    /// # Returns
+    /// A single constraint.
+    #[allow(clippy::too_many_arguments)]
+    pub fn mulh_constraints(
+        rd_val_lo: M31,
+        rd_val_hi: M31,
+        prod_2: M31,
+        prod_3: M31,
+        sign1: M31,
+        sign2: M31,
+        rs1_val: M31,
+        rs2_val: M31,
+        borrow: M31,
+    ) -> Vec<M31> {
+        let two_16 = M31::new(1 << 16);
+        let two_32 = two_16 * two_16;
 
-        let constraints = CpuAir::bit_decomposition_constraints(lo, hi, &bits);
-        
-        for constraint in constraints {
-            assert_eq!(constraint, M31::ZERO);
-        }
+        let high = prod_2 + prod_3 * two_16;
+        let rd_val = rd_val_lo + rd_val_hi * two_16;
+        let corrected = high - sign1 * rs2_val - sign2 * rs1_val + borrow * two_32;
+
+        vec![rd_val - corrected]
     }
 
-    #[test]
-    fn test_bitwise_and_constraint() {
-        // Test: 0b1010 AND 0b1100 = 0b1000
-        let a = 0b1010u32;
-        let b = 0b1100u32;
-        let result = a & b; // = 0b1000
+    /// Evaluate the MULHSU constraint: `rd = high 32 bits of signed(rs1) *
+    /// unsigned(rs2)`. Same correction as [`CpuAir::mulh_constraints`] but
+    /// only `rs1`'s sign applies.
+    ///
+    /// # Returns
+    /// A single constraint.
+    pub fn mulhsu_constraints(
+        rd_val_lo: M31,
+        rd_val_hi: M31,
+        prod_2: M31,
+        prod_3: M31,
+        sign1: M31,
+        rs2_val: M31,
+        borrow: M31,
+    ) -> Vec<M31> {
+        let two_16 = M31::new(1 << 16);
+        let two_32 = two_16 * two_16;
 
-        let bits_a = u32_to_bits(a);
-        let bits_b = u32_to_bits(b);
-        let bits_result = u32_to_bits(result);
+        let high = prod_2 + prod_3 * two_16;
+        let rd_val = rd_val_lo + rd_val_hi * two_16;
+        let corrected = high - sign1 * rs2_val + borrow * two_32;
 
-        let constraints = CpuAir::bitwise_and_constraints(&bits_a, &bits_b, &bits_result);
-        
-        assert_eq!(constraints.len(), 32);
-        for constraint in constraints {
-            assert_eq!(constraint, M31::ZERO);
-        }
+        vec![rd_val - corrected]
     }
 
-    #[test]
-    fn test_bitwise_and_comprehensive() {
-        // Test multiple cases
-        let test_cases = [
-            (0x00000000, 0x00000000, 0x00000000),
-            (0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF),
-            (0xAAAAAAAA, 0x55555555, 0x00000000),
-            (0x12345678, 0xABCDEF00, 0x02044600),
-        ];
+    /// Evaluate the Euclidean-division identity shared by DIV/DIVU/REM/
+    /// REMU: `dividend = quotient*divisor + remainder`. Builds
+    /// `quotient*divisor` via [`CpuAir::mul_product_constraints`] and adds
+    /// `remainder` on top via [`CpuAir::add_constraint`] (its selector
+    /// pinned to 1, since the addition is unconditional here);
+    /// `0 <= remainder < divisor` is the range-check subsystem's job, not
+    /// this constraint's.
+    ///
+    /// A valid `(quotient, remainder)` pair never makes `quotient*divisor`
+    /// exceed 32 bits, since `dividend` itself doesn't — so the product's
+    /// top two limbs (`prod_2`, `prod_3`) must come out to zero.
+    ///
+    /// # Returns
+    /// 8 constraints: the 4 product-limb equations, the 2 zero-top-limb
+    /// checks, and the 2 limb equations adding `remainder` to reconstruct
+    /// `dividend`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn div_rem_identity_constraints(
+        quotient_lo: M31,
+        quotient_hi: M31,
+        divisor_lo: M31,
+        divisor_hi: M31,
+        remainder_lo: M31,
+        remainder_hi: M31,
+        dividend_lo: M31,
+        dividend_hi: M31,
+        prod_0: M31,
+        prod_1: M31,
+        prod_2: M31,
+        prod_3: M31,
+        mul_carry_0: M31,
+        mul_carry_1: M31,
+        mul_carry_2: M31,
+        add_carry: M31,
+    ) -> Vec<M31> {
+        let mut constraints = Self::mul_product_constraints(
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mul_carry_0,
+            mul_carry_1,
+            mul_carry_2,
+        );
 
-        for (a, b, expected) in test_cases {
-            let bits_a = u32_to_bits(a);
-            let bits_b = u32_to_bits(b);
-            let bits_result = u32_to_bits(expected);
+        constraints.push(prod_2);
+        constraints.push(prod_3);
+
+        let (sum_lo, sum_hi) = Self::add_constraint(
+            M31::ONE,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            remainder_lo,
+            remainder_hi,
+            add_carry,
+        );
+        constraints.push(sum_lo);
+        constraints.push(sum_hi);
 
-            let constraints = CpuAir::bitwise_and_constraints(&bits_a, &bits_b, &bits_result);
-            
-            for (i, constraint) in constraints.iter().enumerate() {
-                assert_eq!(*constraint, M31::ZERO, 
-                    "AND failed for case ({:#x}, {:#x}), bit {}", a, b, i);
-            }
-        }
+        constraints
     }
 
-    #[test]
-    fn test_bitwise_or_constraint() {
-        // Test: 0b1010 OR 0b1100 = 0b1110
-        let a = 0b1010u32;
-        let b = 0b1100u32;
-        let result = a | b; // = 0b1110
+    /// Evaluate the two RISC-V division edge cases that override the
+    /// Euclidean identity above instead of satisfying it: `divisor == 0`
+    /// forces `quotient = -1` (all ones) and `remainder = dividend`;
+    /// signed overflow (`dividend == INT_MIN`, `divisor == -1`) forces
+    /// `quotient = INT_MIN` and `remainder = 0`. `is_signed_overflow`
+    /// should always be 0 for DIVU/REMU, which have no sign and so no
+    /// overflow case.
+    ///
+    /// # Returns
+    /// 8 constraints (4 per edge case).
+    #[allow(clippy::too_many_arguments)]
+    pub fn div_edge_case_constraints(
+        is_divisor_zero: M31,
+        is_signed_overflow: M31,
+        quotient_lo: M31,
+        quotient_hi: M31,
+        remainder_lo: M31,
+        remainder_hi: M31,
+        dividend_lo: M31,
+        dividend_hi: M31,
+    ) -> Vec<M31> {
+        let all_ones = M31::new(0xFFFF);
+        let int_min_hi = M31::new(0x8000);
+
+        vec![
+            is_divisor_zero * (quotient_lo - all_ones),
+            is_divisor_zero * (quotient_hi - all_ones),
+            is_divisor_zero * (remainder_lo - dividend_lo),
+            is_divisor_zero * (remainder_hi - dividend_hi),
+            is_signed_overflow * quotient_lo,
+            is_signed_overflow * (quotient_hi - int_min_hi),
+            is_signed_overflow * remainder_lo,
+            is_signed_overflow * remainder_hi,
+        ]
+    }
 
-        let bits_a = u32_to_bits(a);
-        let bits_b = u32_to_bits(b);
-        let bits_result = u32_to_bits(result);
+    /// Evaluate the full DIV/DIVU constraint: binds `rd` to the quotient
+    /// and folds in both [`CpuAir::div_rem_identity_constraints`] (the
+    /// normal case) and [`CpuAir::div_edge_case_constraints`] (division by
+    /// zero and, for DIV only, signed overflow).
+    ///
+    /// # Returns
+    /// 18 constraints (8 identity + 8 edge case + 2 rd-binding).
+    #[allow(clippy::too_many_arguments)]
+    pub fn div_constraints(
+        rd_val_lo: M31,
+        rd_val_hi: M31,
+        is_divisor_zero: M31,
+        is_signed_overflow: M31,
+        quotient_lo: M31,
+        quotient_hi: M31,
+        divisor_lo: M31,
+        divisor_hi: M31,
+        remainder_lo: M31,
+        remainder_hi: M31,
+        dividend_lo: M31,
+        dividend_hi: M31,
+        prod_0: M31,
+        prod_1: M31,
+        prod_2: M31,
+        prod_3: M31,
+        mul_carry_0: M31,
+        mul_carry_1: M31,
+        mul_carry_2: M31,
+        add_carry: M31,
+    ) -> Vec<M31> {
+        let mut constraints = Self::div_rem_identity_constraints(
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mul_carry_0,
+            mul_carry_1,
+            mul_carry_2,
+            add_carry,
+        );
+        // Signed overflow lifts the zero-top-limb checks: INT_MIN * -1 as
+        // unsigned patterns exceeds 32 bits, though its low word still
+        // reconstructs the dividend.
+        let not_overflow = M31::ONE - is_signed_overflow;
+        constraints[4] = not_overflow * constraints[4];
+        constraints[5] = not_overflow * constraints[5];
+        constraints.extend(Self::div_edge_case_constraints(
+            is_divisor_zero,
+            is_signed_overflow,
+            quotient_lo,
+            quotient_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+        ));
+        constraints.push(rd_val_lo - quotient_lo);
+        constraints.push(rd_val_hi - quotient_hi);
 
-        let constraints = CpuAir::bitwise_or_constraints(&bits_a, &bits_b, &bits_result);
-        
-        assert_eq!(constraints.len(), 32);
-        for constraint in constraints {
-            assert_eq!(constraint, M31::ZERO);
-        }
+        constraints
     }
 
-    #[test]
-    fn test_bitwise_or_comprehensive() {
-        let test_cases = [
-            (0x00000000, 0x00000000, 0x00000000),
-            (0xFFFFFFFF, 0x00000000, 0xFFFFFFFF),
-            (0xAAAAAAAA, 0x55555555, 0xFFFFFFFF),
-            (0x12345678, 0xABCDEF00, 0xBBFDFF78),
+    /// Evaluate the full REM/REMU constraint: binds `rd` to the remainder
+    /// and folds in both [`CpuAir::div_rem_identity_constraints`] (the
+    /// normal case) and [`CpuAir::div_edge_case_constraints`] (division by
+    /// zero and, for REM only, signed overflow). The quotient/remainder
+    /// witnesses are the same ones DIV/DIVU would use for the same
+    /// dividend/divisor pair; only which one feeds `rd` differs.
+    ///
+    /// # Returns
+    /// 18 constraints (8 identity + 8 edge case + 2 rd-binding).
+    #[allow(clippy::too_many_arguments)]
+    pub fn rem_constraints(
+        rd_val_lo: M31,
+        rd_val_hi: M31,
+        is_divisor_zero: M31,
+        is_signed_overflow: M31,
+        quotient_lo: M31,
+        quotient_hi: M31,
+        divisor_lo: M31,
+        divisor_hi: M31,
+        remainder_lo: M31,
+        remainder_hi: M31,
+        dividend_lo: M31,
+        dividend_hi: M31,
+        prod_0: M31,
+        prod_1: M31,
+        prod_2: M31,
+        prod_3: M31,
+        mul_carry_0: M31,
+        mul_carry_1: M31,
+        mul_carry_2: M31,
+        add_carry: M31,
+    ) -> Vec<M31> {
+        let mut constraints = Self::div_rem_identity_constraints(
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mul_carry_0,
+            mul_carry_1,
+            mul_carry_2,
+            add_carry,
+        );
+        // Signed overflow lifts the zero-top-limb checks: INT_MIN * -1 as
+        // unsigned patterns exceeds 32 bits, though its low word still
+        // reconstructs the dividend.
+        let not_overflow = M31::ONE - is_signed_overflow;
+        constraints[4] = not_overflow * constraints[4];
+        constraints[5] = not_overflow * constraints[5];
+        constraints.extend(Self::div_edge_case_constraints(
+            is_divisor_zero,
+            is_signed_overflow,
+            quotient_lo,
+            quotient_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+        ));
+        constraints.push(rd_val_lo - remainder_lo);
+        constraints.push(rd_val_hi - remainder_hi);
+
+        constraints
+    }
+
+    /// Evaluate one step of a LogUp-style lookup-side accumulator: `inv`
+    /// is the prover-supplied witness for `1/(x - value)` against a
+    /// verifier challenge `x`, and `acc`/`acc_next` are consecutive rows
+    /// of the running sum `sum 1/(x - value_i)` that, folded across the
+    /// whole trace, must equal the range-check table's own multiplicity-
+    /// weighted running sum from [`CpuAir::logup_table_step`] — see
+    /// [`CpuAir::logup_close_constraint`].
+    ///
+    /// # Returns
+    /// 2 constraints: the inverse relation `inv * (x - value) = 1`, and
+    /// the accumulator update `acc_next - acc - inv = 0`. Both stay
+    /// degree ≤ 2 once `inv` has cleared the `1/(x - value)` denominator.
+    #[inline]
+    pub fn logup_lookup_step(x: M31, value: M31, inv: M31, acc: M31, acc_next: M31) -> Vec<M31> {
+        vec![inv * (x - value) - M31::ONE, acc_next - acc - inv]
+    }
+
+    /// Evaluate one step of the range-check table's own LogUp
+    /// accumulator: same shape as [`CpuAir::logup_lookup_step`], but
+    /// weighted by `multiplicity` (how many times `table_entry` was
+    /// looked up across the whole trace) instead of contributing `1` per
+    /// row.
+    ///
+    /// # Returns
+    /// 2 constraints: `inv * (x - table_entry) = 1`, and
+    /// `acc_next - acc - multiplicity * inv = 0`.
+    #[inline]
+    pub fn logup_table_step(
+        x: M31,
+        table_entry: M31,
+        multiplicity: M31,
+        inv: M31,
+        acc: M31,
+        acc_next: M31,
+    ) -> Vec<M31> {
+        vec![
+            inv * (x - table_entry) - M31::ONE,
+            acc_next - acc - multiplicity * inv,
+        ]
+    }
+
+    /// Evaluate the LogUp closing constraint: once the lookup side
+    /// ([`CpuAir::logup_lookup_step`]) and the table side
+    /// ([`CpuAir::logup_table_step`]) have each folded across every row,
+    /// their final accumulator values must agree — this is what actually
+    /// proves every looked-up value appears in the table.
+    #[inline]
+    pub fn logup_close_constraint(lookup_acc_final: M31, table_acc_final: M31) -> M31 {
+        lookup_acc_final - table_acc_final
+    }
+
+    /// Evaluate a single range-check-to-16-bits lookup step: `value` must
+    /// appear as some row of the committed `[0, 2^16)` table, proven via
+    /// the LogUp argument in [`CpuAir::logup_lookup_step`]. Closes the
+    /// soundness gap where nothing forces a limb like `add_constraint`'s
+    /// `rd_val_lo`/`rd_val_hi` into its intended 16-bit range.
+    ///
+    /// # Returns
+    /// 2 constraints (see [`CpuAir::logup_lookup_step`]).
+    #[inline]
+    pub fn range_check_16(x: M31, value: M31, inv: M31, acc: M31, acc_next: M31) -> Vec<M31> {
+        Self::logup_lookup_step(x, value, inv, acc, acc_next)
+    }
+
+    /// Evaluate a binary range check: `value` must be 0 or 1. A 2-row
+    /// lookup table buys nothing a direct booleanity constraint doesn't
+    /// already give for free — the same trick
+    /// `shift_selector_constraints` uses for its selectors — so carries
+    /// (which only ever need to be 0 or 1, unlike the 16-bit limbs above)
+    /// are checked this way instead of going through LogUp.
+    #[inline]
+    pub fn range_check_bit(value: M31) -> M31 {
+        value * (value - M31::ONE)
+    }
+
+    /// Evaluate ADD plus the range checks its limb decomposition depends
+    /// on for soundness: [`CpuAir::add_constraint`] alone never
+    /// constrains `carry` to be binary or `rd_val_lo`/`rd_val_hi` into
+    /// `[0, 2^16)`, so a malicious prover could pick an out-of-range carry
+    /// or limb that still satisfies the arithmetic identity while
+    /// reconstructing to the wrong 32-bit value.
+    ///
+    /// # Returns
+    /// `add_constraint`'s 2 constraints, followed by the carry binary
+    /// check and a [`CpuAir::range_check_16`] lookup step (2 constraints
+    /// each) for `rd_val_lo` and `rd_val_hi` — 7 in total.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_range_checked_constraints(
+        is_add: M31,
+        rd_val_lo: M31,
+        rd_val_hi: M31,
+        rs1_val_lo: M31,
+        rs1_val_hi: M31,
+        rs2_val_lo: M31,
+        rs2_val_hi: M31,
+        carry: M31,
+        x: M31,
+        lo_inv: M31,
+        lo_acc: M31,
+        lo_acc_next: M31,
+        hi_inv: M31,
+        hi_acc: M31,
+        hi_acc_next: M31,
+    ) -> Vec<M31> {
+        let (c1, c2) = Self::add_constraint(
+            is_add, rd_val_lo, rd_val_hi, rs1_val_lo, rs1_val_hi, rs2_val_lo, rs2_val_hi, carry,
+        );
+
+        let mut constraints = vec![c1, c2, Self::range_check_bit(carry)];
+        constraints.extend(Self::range_check_16(x, rd_val_lo, lo_inv, lo_acc, lo_acc_next));
+        constraints.extend(Self::range_check_16(x, rd_val_hi, hi_inv, hi_acc, hi_acc_next));
+
+        constraints
+    }
+
+    /// Evaluate the MUL product decomposition plus the range checks it
+    /// depends on for soundness: [`CpuAir::mul_product_constraints`]
+    /// alone never constrains its carries into their true range or its
+    /// `prod_0..prod_3` limbs into `[0, 2^16)`, the same gap
+    /// [`CpuAir::add_range_checked_constraints`] closes for ADD. Shared
+    /// by MUL/MULH*/DIV*/REM*, since they all bottom out in this same
+    /// product decomposition.
+    ///
+    /// `carry_0` and `carry_2` are each at most a 16-bit-limb product
+    /// (plus a smaller addend) shifted down by 16, so both stay inside
+    /// `[0, 2^16)` and a single [`CpuAir::range_check_16`] suffices for
+    /// each. `carry_1` sums *two* such products before shifting
+    /// (`rs1_lo*rs2_hi + rs1_hi*rs2_lo + carry_0`) and can therefore reach
+    /// 17 bits — the caller additionally supplies its decomposition into
+    /// a high bit `carry_1_hi` and a 16-bit remainder `carry_1_lo`, each
+    /// range-checked separately, the same way a 32-bit value splits into
+    /// lo/hi limbs elsewhere in this module.
+    ///
+    /// # Returns
+    /// `mul_product_constraints`'s 4 constraints, followed by the
+    /// `carry_1` decomposition check, a binary check on `carry_1_hi`, and
+    /// 7 `range_check_16` lookup steps (2 constraints each) for
+    /// `prod_0..prod_3`, `carry_0`, `carry_1_lo`, and `carry_2` — 20 in
+    /// total.
+    #[allow(clippy::too_many_arguments)]
+    pub fn mul_range_checked_constraints(
+        rs1_lo: M31,
+        rs1_hi: M31,
+        rs2_lo: M31,
+        rs2_hi: M31,
+        prod_0: M31,
+        prod_1: M31,
+        prod_2: M31,
+        prod_3: M31,
+        carry_0: M31,
+        carry_1: M31,
+        carry_2: M31,
+        carry_1_lo: M31,
+        carry_1_hi: M31,
+        x: M31,
+        invs: [M31; 7],
+        accs: [M31; 7],
+        accs_next: [M31; 7],
+    ) -> Vec<M31> {
+        let mut constraints = Self::mul_product_constraints(
+            rs1_lo, rs1_hi, rs2_lo, rs2_hi, prod_0, prod_1, prod_2, prod_3, carry_0, carry_1,
+            carry_2,
+        );
+
+        let two_16 = M31::new(1 << 16);
+        constraints.push(carry_1 - (carry_1_hi * two_16 + carry_1_lo));
+        constraints.push(Self::range_check_bit(carry_1_hi));
+
+        let values = [prod_0, prod_1, prod_2, prod_3, carry_0, carry_1_lo, carry_2];
+        for i in 0..7 {
+            constraints.extend(Self::range_check_16(
+                x,
+                values[i],
+                invs[i],
+                accs[i],
+                accs_next[i],
+            ));
+        }
+
+        constraints
+    }
+
+    /// Evaluate the shared limb-wise borrow chain behind SLTU/SLT:
+    /// subtracting `rs2` from `rs1` one 16-bit limb at a time the same
+    /// way `add_constraint` threads a carry, except each limb's borrow
+    /// witness now signals "underflowed" rather than "overflowed". The
+    /// final borrow is 1 exactly when `rs1 < rs2` (as 32-bit unsigned
+    /// values) — the SLTU result directly. SLT reaches the same chain by
+    /// flipping both operands' sign bits first (see
+    /// [`CpuAir::slt_constraints`]), turning a signed comparison into
+    /// this unsigned one.
+    ///
+    /// # Returns
+    /// 4 constraints: the two limb-wise subtraction identities, plus
+    /// booleanity on `borrow_lo` and `borrow_hi`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn borrow_chain_constraints(
+        rs1_lo: M31,
+        rs1_hi: M31,
+        rs2_lo: M31,
+        rs2_hi: M31,
+        diff_lo: M31,
+        diff_hi: M31,
+        borrow_lo: M31,
+        borrow_hi: M31,
+    ) -> Vec<M31> {
+        let two_16 = M31::new(1 << 16);
+
+        vec![
+            rs1_lo - rs2_lo - diff_lo + borrow_lo * two_16,
+            rs1_hi - rs2_hi - borrow_lo - diff_hi + borrow_hi * two_16,
+            Boolean::new(borrow_lo).booleanity_constraint(),
+            Boolean::new(borrow_hi).booleanity_constraint(),
+        ]
+    }
+
+    /// Evaluate the SLTU constraint: `rd = 1` if `rs1 < rs2` (unsigned),
+    /// else `0`. `borrow_hi` from [`CpuAir::borrow_chain_constraints`] is
+    /// exactly that indicator.
+    ///
+    /// # Returns
+    /// `borrow_chain_constraints`'s 4 constraints, plus the `rd`-binding
+    /// constraint — 5 in total.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sltu_constraints(
+        rd_val: M31,
+        rs1_lo: M31,
+        rs1_hi: M31,
+        rs2_lo: M31,
+        rs2_hi: M31,
+        diff_lo: M31,
+        diff_hi: M31,
+        borrow_lo: M31,
+        borrow_hi: M31,
+    ) -> Vec<M31> {
+        let mut constraints = Self::borrow_chain_constraints(
+            rs1_lo, rs1_hi, rs2_lo, rs2_hi, diff_lo, diff_hi, borrow_lo, borrow_hi,
+        );
+        constraints.push(rd_val - borrow_hi);
+        constraints
+    }
+
+    /// Evaluate the SLT constraint: `rd = 1` if `rs1 < rs2` (signed), else
+    /// `0`. Flips both operands' sign bits (bit 31) before running the
+    /// same unsigned borrow chain SLTU uses — the standard trick that
+    /// turns a signed comparison into an unsigned one:
+    /// `rs1_hi_flipped = rs1_hi XOR 0x8000`, expressed algebraically as
+    /// `rs1_hi + 0x8000 - 0x10000*sign1`.
+    ///
+    /// # Arguments
+    /// * `sign1`, `sign2` - bit 31 of `rs1`, `rs2`
+    ///
+    /// # Returns
+    /// 5 constraints (see [`CpuAir::sltu_constraints`]).
+    #[allow(clippy::too_many_arguments)]
+    pub fn slt_constraints(
+        rd_val: M31,
+        rs1_lo: M31,
+        rs1_hi: M31,
+        rs2_lo: M31,
+        rs2_hi: M31,
+        sign1: M31,
+        sign2: M31,
+        diff_lo: M31,
+        diff_hi: M31,
+        borrow_lo: M31,
+        borrow_hi: M31,
+    ) -> Vec<M31> {
+        let sign_flip = M31::new(0x8000);
+        let two_16 = M31::new(1 << 16);
+
+        let rs1_hi_flipped = rs1_hi + sign_flip - two_16 * sign1;
+        let rs2_hi_flipped = rs2_hi + sign_flip - two_16 * sign2;
+
+        Self::sltu_constraints(
+            rd_val, rs1_lo, rs1_hi_flipped, rs2_lo, rs2_hi_flipped, diff_lo, diff_hi, borrow_lo,
+            borrow_hi,
+        )
+    }
+
+    /// Evaluate the taken/not-taken PC step every conditional branch
+    /// shares: `next_pc` is `pc + imm` when `taken` and `pc + 4` (the same
+    /// fallthrough [`CpuAir::pc_increment_constraint`] uses) otherwise.
+    #[inline]
+    pub fn branch_pc_constraint(is_branch: M31, pc: M31, next_pc: M31, imm: M31, taken: M31) -> M31 {
+        let four = M31::new(4);
+        let one = M31::ONE;
+        let branch_step = taken * (next_pc - pc - imm) + (one - taken) * (next_pc - pc - four);
+        is_branch * branch_step
+    }
+
+    /// Evaluate the binding of `taken` to the comparison a branch's
+    /// funct3 selects (BEQ/BNE/BLT/BGE/BLTU/BGEU): exactly one of
+    /// `is_beq..is_bgeu` is 1, and each contributes its own linear term so
+    /// the whole expression stays degree ≤ 2.
+    ///
+    /// # Arguments
+    /// * `eq_result` - 1 iff `rs1 == rs2`
+    /// * `lt_result` - [`CpuAir::slt_constraints`]'s `rs1 < rs2` (signed)
+    /// * `ltu_result` - [`CpuAir::sltu_constraints`]'s `rs1 < rs2`
+    ///   (unsigned)
+    #[allow(clippy::too_many_arguments)]
+    pub fn branch_taken_constraint(
+        is_beq: M31,
+        is_bne: M31,
+        is_blt: M31,
+        is_bge: M31,
+        is_bltu: M31,
+        is_bgeu: M31,
+        eq_result: M31,
+        lt_result: M31,
+        ltu_result: M31,
+        taken: M31,
+    ) -> M31 {
+        let one = M31::ONE;
+        let expected = is_beq * eq_result
+            + is_bne * (one - eq_result)
+            + is_blt * lt_result
+            + is_bge * (one - lt_result)
+            + is_bltu * ltu_result
+            + is_bgeu * (one - ltu_result);
+
+        taken - expected
+    }
+
+    /// Evaluate the full branch constraint: binds `taken` to the
+    /// comparison selected by the branch's funct3 via
+    /// [`CpuAir::branch_taken_constraint`], then folds in the
+    /// taken/not-taken PC step via [`CpuAir::branch_pc_constraint`].
+    ///
+    /// # Returns
+    /// 2 constraints.
+    #[allow(clippy::too_many_arguments)]
+    pub fn branch_constraints(
+        is_branch: M31,
+        is_beq: M31,
+        is_bne: M31,
+        is_blt: M31,
+        is_bge: M31,
+        is_bltu: M31,
+        is_bgeu: M31,
+        eq_result: M31,
+        lt_result: M31,
+        ltu_result: M31,
+        pc: M31,
+        next_pc: M31,
+        imm: M31,
+        taken: M31,
+    ) -> Vec<M31> {
+        vec![
+            Self::branch_taken_constraint(
+                is_beq, is_bne, is_blt, is_bge, is_bltu, is_bgeu, eq_result, lt_result,
+                ltu_result, taken,
+            ),
+            Self::branch_pc_constraint(is_branch, pc, next_pc, imm, taken),
+        ]
+    }
+
+    /// Evaluate one step of a LogUp-style lookup-side accumulator drawing
+    /// its challenge from the QM31 extension field rather than M31, the
+    /// default path for any lookup or permutation argument whose table
+    /// isn't provably small (unlike [`CpuAir::range_check_16`]'s 2^16-row
+    /// table, where an M31 challenge's ~2^31 space already gives ample
+    /// soundness margin). The base field only has ~2^31 elements, so a
+    /// lookup argument challenged there gives a soundness error on the
+    /// order of `num_rows * num_lookups / 2^31`; drawing `x` from QM31
+    /// instead pushes that down to ~1/2^124.
+    ///
+    /// `value` stays in the base field (it's an actual trace cell), but
+    /// `x`/`inv`/`acc`/`acc_next` all live in QM31. Each is represented by
+    /// its four M31 components, since a constraint here must still come
+    /// out as a flat M31 vector like every other constraint in this file.
+    ///
+    /// # Returns
+    /// 8 constraints: the 4 components of `inv * (x - value) - 1 = 0`,
+    /// followed by the 4 components of `acc_next - acc - inv = 0`.
+    pub fn logup_lookup_step_ext(
+        x: QM31,
+        value: M31,
+        inv: QM31,
+        acc: QM31,
+        acc_next: QM31,
+    ) -> Vec<M31> {
+        let inv_relation = inv * (x - QM31::from(value)) - QM31::from(M31::ONE);
+        let acc_relation = acc_next - acc - inv;
+
+        vec![
+            inv_relation.c0,
+            inv_relation.c1,
+            inv_relation.c2,
+            inv_relation.c3,
+            acc_relation.c0,
+            acc_relation.c1,
+            acc_relation.c2,
+            acc_relation.c3,
+        ]
+    }
+
+    /// Evaluate one step of a lookup table's own QM31 LogUp accumulator:
+    /// same shape as [`CpuAir::logup_lookup_step_ext`], but weighted by
+    /// `multiplicity` (how many times `table_entry` was looked up across
+    /// the whole trace) instead of contributing `1` per row. `multiplicity`
+    /// is a small row count, so it stays in the base field.
+    ///
+    /// # Returns
+    /// 8 constraints (see [`CpuAir::logup_lookup_step_ext`]'s shape):
+    /// the 4 components of `inv * (x - table_entry) - 1 = 0`, followed by
+    /// the 4 components of `acc_next - acc - multiplicity * inv = 0`.
+    pub fn logup_table_step_ext(
+        x: QM31,
+        table_entry: M31,
+        multiplicity: M31,
+        inv: QM31,
+        acc: QM31,
+        acc_next: QM31,
+    ) -> Vec<M31> {
+        let inv_relation = inv * (x - QM31::from(table_entry)) - QM31::from(M31::ONE);
+        let acc_relation = acc_next - acc - QM31::from(multiplicity) * inv;
+
+        vec![
+            inv_relation.c0,
+            inv_relation.c1,
+            inv_relation.c2,
+            inv_relation.c3,
+            acc_relation.c0,
+            acc_relation.c1,
+            acc_relation.c2,
+            acc_relation.c3,
+        ]
+    }
+
+    /// Evaluate the QM31 LogUp closing constraint: once the lookup side
+    /// ([`CpuAir::logup_lookup_step_ext`]) and the table side
+    /// ([`CpuAir::logup_table_step_ext`]) have each folded across every
+    /// row, their final accumulators must agree component-wise — this is
+    /// what actually proves every looked-up value appears in the table.
+    /// The same constraint backs a permutation argument (e.g. memory
+    /// read/write consistency): both directions fold into a QM31
+    /// accumulator and must close to the same value.
+    ///
+    /// # Returns
+    /// 4 constraints, one per QM31 component.
+    pub fn logup_close_constraint_ext(lookup_acc_final: QM31, table_acc_final: QM31) -> Vec<M31> {
+        let diff = lookup_acc_final - table_acc_final;
+        vec![diff.c0, diff.c1, diff.c2, diff.c3]
+    }
+}
+
+fn alloc(next: &mut usize) -> usize {
+    let c = *next;
+    *next += 1;
+    c
+}
+
+fn alloc_array<const N: usize>(next: &mut usize) -> [usize; N] {
+    core::array::from_fn(|_| alloc(next))
+}
+
+/// Column roles for one row of the uniform per-step RISC-V AIR: instead of
+/// the prover's raw `trace_columns: Vec<Vec<M31>>` carrying no structure,
+/// the same layout is stamped out once and logically repeated for every
+/// cycle of the fetch-decode-execute loop. Column indices are assigned
+/// once by [`StepLayout::new`] and then shared by [`StepTraceBuilder`]
+/// (witness generation) and [`step_row_constraints`] /
+/// [`step_transition_constraints`] (constraint evaluation), so the two
+/// can never disagree about which column is which.
+///
+/// Bitwise/shift operations (AND, OR, XOR, SLL, SRL, SRA) share one
+/// `bits_a`/`bits_b`/`bits_result`/`shift_selectors` block rather than each
+/// getting its own 32-wide array, and DIV/REM/MUL/MULH* share one set of
+/// partial-product witnesses — otherwise this table would need a column
+/// per opcode's private witnesses instead of per distinct *shape* of
+/// witness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::too_many_arguments)]
+pub struct StepLayout {
+    pub pc: usize,
+    pub next_pc: usize,
+    pub imm: usize,
+    pub rs1_idx: usize,
+    pub rs2_idx: usize,
+    pub rd_idx: usize,
+    pub rs1_val_lo: usize,
+    pub rs1_val_hi: usize,
+    pub rs2_val_lo: usize,
+    pub rs2_val_hi: usize,
+    pub rd_val_lo: usize,
+    pub rd_val_hi: usize,
+    pub is_write_x0: usize,
+
+    // Opcode selectors; exactly one is 1 per row (see
+    // [`StepLayout::opcode_selectors`]).
+    pub is_add: usize,
+    pub is_and: usize,
+    pub is_or: usize,
+    pub is_xor: usize,
+    pub is_sll: usize,
+    pub is_srl: usize,
+    pub is_sra: usize,
+    pub is_slt: usize,
+    pub is_sltu: usize,
+    pub is_lui: usize,
+    pub is_auipc: usize,
+    pub is_jal: usize,
+    pub is_jalr: usize,
+    pub is_beq: usize,
+    pub is_bne: usize,
+    pub is_blt: usize,
+    pub is_bge: usize,
+    pub is_bltu: usize,
+    pub is_bgeu: usize,
+    pub is_mul: usize,
+    pub is_mulh: usize,
+    pub is_mulhu: usize,
+    pub is_mulhsu: usize,
+    pub is_div: usize,
+    pub is_divu: usize,
+    pub is_rem: usize,
+    pub is_remu: usize,
+    pub is_load: usize,
+    pub is_store: usize,
+
+    // Bitwise/shift witnesses, shared across AND/OR/XOR/SLL/SRL/SRA.
+    pub bits_a: [usize; 32],
+    pub bits_b: [usize; 32],
+    pub bits_result: [usize; 32],
+    pub shift_selectors: [usize; 32],
+    pub shift_amount: usize,
+    pub pow2: usize,
+
+    pub add_carry: usize,
+
+    // Shared MUL/MULH*/DIV/REM partial-product witnesses.
+    pub prod: [usize; 4],
+    pub mul_carry: [usize; 3],
+
+    // DIV/REM-only witnesses; dividend/divisor reuse rs1_val/rs2_val.
+    pub quotient_lo: usize,
+    pub quotient_hi: usize,
+    pub remainder_lo: usize,
+    pub remainder_hi: usize,
+    pub is_divisor_zero: usize,
+    pub is_signed_overflow: usize,
+
+    // SLT/SLTU/branch comparison witnesses.
+    pub sign1: usize,
+    pub sign2: usize,
+    pub diff_lo: usize,
+    pub diff_hi: usize,
+    pub borrow_lo: usize,
+    pub borrow_hi: usize,
+    pub taken: usize,
+    pub eq_result: usize,
+    pub lt_result: usize,
+    pub ltu_result: usize,
+
+    // Memory op columns.
+    pub mem_addr_lo: usize,
+    pub mem_addr_hi: usize,
+    pub mem_val_lo: usize,
+    pub mem_val_hi: usize,
+    /// The single field element this row's load/store looks up in the
+    /// `memory` module's permutation argument; how `mem_addr_*`/
+    /// `mem_val_*` combine into it belongs to that subsystem once it
+    /// lands, this layout just reserves the slot it feeds.
+    pub mem_record: usize,
+
+    // Cross-module consistency: a QM31 LogUp-style running sum (see
+    // [`CpuAir::logup_lookup_step_ext`]) over every load/store this step
+    // issues, which the `memory` module's own table-side accumulator must
+    // close against via [`CpuAir::logup_close_constraint_ext`]. The same
+    // shape also backs the instruction-lookup argument (`instr_*`) that
+    // ties each step's `pc`/opcode selectors back to the program's
+    // instruction table.
+    pub mem_perm_inv: [usize; 4],
+    pub mem_perm_acc: [usize; 4],
+    pub instr_record: usize,
+    pub instr_perm_inv: [usize; 4],
+    pub instr_perm_acc: [usize; 4],
+
+    pub num_columns: usize,
+}
+
+impl StepLayout {
+    /// Assign column indices for one step row in a fixed, deterministic
+    /// order. Witness generation ([`StepTraceBuilder`]) and constraint
+    /// evaluation ([`step_row_constraints`], [`step_transition_constraints`])
+    /// both index through the same `StepLayout`, so this is the only place
+    /// the column order is decided.
+    #[allow(clippy::too_many_lines)]
+    pub fn new() -> Self {
+        let mut next = 0usize;
+
+        let layout = StepLayout {
+            pc: alloc(&mut next),
+            next_pc: alloc(&mut next),
+            imm: alloc(&mut next),
+            rs1_idx: alloc(&mut next),
+            rs2_idx: alloc(&mut next),
+            rd_idx: alloc(&mut next),
+            rs1_val_lo: alloc(&mut next),
+            rs1_val_hi: alloc(&mut next),
+            rs2_val_lo: alloc(&mut next),
+            rs2_val_hi: alloc(&mut next),
+            rd_val_lo: alloc(&mut next),
+            rd_val_hi: alloc(&mut next),
+            is_write_x0: alloc(&mut next),
+
+            is_add: alloc(&mut next),
+            is_and: alloc(&mut next),
+            is_or: alloc(&mut next),
+            is_xor: alloc(&mut next),
+            is_sll: alloc(&mut next),
+            is_srl: alloc(&mut next),
+            is_sra: alloc(&mut next),
+            is_slt: alloc(&mut next),
+            is_sltu: alloc(&mut next),
+            is_lui: alloc(&mut next),
+            is_auipc: alloc(&mut next),
+            is_jal: alloc(&mut next),
+            is_jalr: alloc(&mut next),
+            is_beq: alloc(&mut next),
+            is_bne: alloc(&mut next),
+            is_blt: alloc(&mut next),
+            is_bge: alloc(&mut next),
+            is_bltu: alloc(&mut next),
+            is_bgeu: alloc(&mut next),
+            is_mul: alloc(&mut next),
+            is_mulh: alloc(&mut next),
+            is_mulhu: alloc(&mut next),
+            is_mulhsu: alloc(&mut next),
+            is_div: alloc(&mut next),
+            is_divu: alloc(&mut next),
+            is_rem: alloc(&mut next),
+            is_remu: alloc(&mut next),
+            is_load: alloc(&mut next),
+            is_store: alloc(&mut next),
+
+            bits_a: alloc_array(&mut next),
+            bits_b: alloc_array(&mut next),
+            bits_result: alloc_array(&mut next),
+            shift_selectors: alloc_array(&mut next),
+            shift_amount: alloc(&mut next),
+            pow2: alloc(&mut next),
+
+            add_carry: alloc(&mut next),
+
+            prod: alloc_array(&mut next),
+            mul_carry: alloc_array(&mut next),
+
+            quotient_lo: alloc(&mut next),
+            quotient_hi: alloc(&mut next),
+            remainder_lo: alloc(&mut next),
+            remainder_hi: alloc(&mut next),
+            is_divisor_zero: alloc(&mut next),
+            is_signed_overflow: alloc(&mut next),
+
+            sign1: alloc(&mut next),
+            sign2: alloc(&mut next),
+            diff_lo: alloc(&mut next),
+            diff_hi: alloc(&mut next),
+            borrow_lo: alloc(&mut next),
+            borrow_hi: alloc(&mut next),
+            taken: alloc(&mut next),
+            eq_result: alloc(&mut next),
+            lt_result: alloc(&mut next),
+            ltu_result: alloc(&mut next),
+
+            mem_addr_lo: alloc(&mut next),
+            mem_addr_hi: alloc(&mut next),
+            mem_val_lo: alloc(&mut next),
+            mem_val_hi: alloc(&mut next),
+            mem_record: alloc(&mut next),
+
+            mem_perm_inv: alloc_array(&mut next),
+            mem_perm_acc: alloc_array(&mut next),
+            instr_record: alloc(&mut next),
+            instr_perm_inv: alloc_array(&mut next),
+            instr_perm_acc: alloc_array(&mut next),
+
+            num_columns: 0,
+        };
+
+        StepLayout {
+            num_columns: next,
+            ..layout
+        }
+    }
+
+    /// Every opcode selector column, in the order they're allocated. Used
+    /// for the one-hot constraint ("exactly one opcode is active") and by
+    /// tests that need to zero every selector before setting one.
+    pub fn opcode_selectors(&self) -> [usize; 29] {
+        [
+            self.is_add,
+            self.is_and,
+            self.is_or,
+            self.is_xor,
+            self.is_sll,
+            self.is_srl,
+            self.is_sra,
+            self.is_slt,
+            self.is_sltu,
+            self.is_lui,
+            self.is_auipc,
+            self.is_jal,
+            self.is_jalr,
+            self.is_beq,
+            self.is_bne,
+            self.is_blt,
+            self.is_bge,
+            self.is_bltu,
+            self.is_bgeu,
+            self.is_mul,
+            self.is_mulh,
+            self.is_mulhu,
+            self.is_mulhsu,
+            self.is_div,
+            self.is_divu,
+            self.is_rem,
+            self.is_remu,
+            self.is_load,
+            self.is_store,
+        ]
+    }
+}
+
+impl Default for StepLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Packs a sequence of per-cycle witness rows into the column-major
+/// `Vec<Vec<M31>>` the prover's trace representation expects, validating
+/// each row against a shared [`StepLayout`] as it goes so a malformed row
+/// (wrong width) is caught at trace-construction time rather than
+/// producing a constraint-evaluation panic deep inside the prover.
+pub struct StepTraceBuilder {
+    layout: StepLayout,
+    rows: Vec<Vec<M31>>,
+}
+
+impl StepTraceBuilder {
+    pub fn new(layout: StepLayout) -> Self {
+        Self {
+            layout,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Append one step's row. `row.len()` must equal
+    /// `self.layout.num_columns`.
+    pub fn push_row(&mut self, row: Vec<M31>) {
+        assert_eq!(
+            row.len(),
+            self.layout.num_columns,
+            "step row width does not match StepLayout::num_columns"
+        );
+        self.rows.push(row);
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Transpose the accumulated row-major rows into the prover's
+    /// column-major trace format.
+    pub fn finish(self) -> Vec<Vec<M31>> {
+        let num_cols = self.layout.num_columns;
+        let num_rows = self.rows.len();
+        let mut columns = vec![Vec::with_capacity(num_rows); num_cols];
+        for row in self.rows {
+            for (col, value) in columns.iter_mut().zip(row) {
+                col.push(value);
+            }
+        }
+        columns
+    }
+}
+
+/// Evaluate every constraint that only reads a single step row: the
+/// one-hot opcode selector, and each opcode's own gadget from [`CpuAir`]
+/// gated by its selector column (gadgets like [`CpuAir::add_constraint`]
+/// that already take a selector argument are passed theirs directly;
+/// gadgets that don't (e.g. [`CpuAir::slt_constraints`],
+/// [`CpuAir::div_constraints`]) have every constraint they return
+/// multiplied by the relevant selector here, since on a uniform table
+/// their witness columns are populated for every row, not just rows where
+/// that opcode is active).
+pub fn step_row_constraints(layout: &StepLayout, row: &[M31]) -> Vec<M31> {
+    let c = |i: usize| row[i];
+    let gate = |selector: M31, cs: Vec<M31>| -> Vec<M31> {
+        cs.into_iter().map(|v| selector * v).collect()
+    };
+    let mut constraints = Vec::new();
+
+    let mut selector_sum = M31::ZERO;
+    for &s in &layout.opcode_selectors() {
+        constraints.push(Boolean::new(c(s)).booleanity_constraint());
+        selector_sum = selector_sum + c(s);
+    }
+    constraints.push(selector_sum - M31::ONE);
+
+    constraints.push(CpuAir::x0_zero_constraint(
+        c(layout.is_write_x0),
+        c(layout.rd_val_lo),
+        c(layout.rd_val_hi),
+    ));
+    constraints.push(CpuAir::pc_increment_constraint(
+        c(layout.pc),
+        c(layout.next_pc),
+        c(layout.is_beq) + c(layout.is_bne) + c(layout.is_blt) + c(layout.is_bge)
+            + c(layout.is_bltu) + c(layout.is_bgeu),
+        c(layout.is_jal),
+        c(layout.is_jalr),
+    ));
+
+    let two_16 = M31::new(1 << 16);
+    let rd_val_combined = c(layout.rd_val_lo) + c(layout.rd_val_hi) * two_16;
+    let rs1_val_combined = c(layout.rs1_val_lo) + c(layout.rs1_val_hi) * two_16;
+    let rs2_val_combined = c(layout.rs2_val_lo) + c(layout.rs2_val_hi) * two_16;
+
+    constraints.push(CpuAir::lui_constraint(c(layout.is_lui), rd_val_combined, c(layout.imm)));
+    constraints.push(CpuAir::auipc_constraint(
+        c(layout.is_auipc),
+        rd_val_combined,
+        c(layout.pc),
+        c(layout.imm),
+    ));
+
+    let (add_lo, add_hi) = CpuAir::add_constraint(
+        c(layout.is_add),
+        c(layout.rd_val_lo),
+        c(layout.rd_val_hi),
+        c(layout.rs1_val_lo),
+        c(layout.rs1_val_hi),
+        c(layout.rs2_val_lo),
+        c(layout.rs2_val_hi),
+        c(layout.add_carry),
+    );
+    constraints.push(add_lo);
+    constraints.push(add_hi);
+
+    let bits_a: [M31; 32] = core::array::from_fn(|i| c(layout.bits_a[i]));
+    let bits_b: [M31; 32] = core::array::from_fn(|i| c(layout.bits_b[i]));
+    let bits_result: [M31; 32] = core::array::from_fn(|i| c(layout.bits_result[i]));
+    let shift_selectors: [M31; 32] = core::array::from_fn(|i| c(layout.shift_selectors[i]));
+
+    let is_bitwise_or_shift = c(layout.is_and)
+        + c(layout.is_or)
+        + c(layout.is_xor)
+        + c(layout.is_sll)
+        + c(layout.is_srl)
+        + c(layout.is_sra);
+    constraints.extend(gate(
+        is_bitwise_or_shift,
+        CpuAir::bit_decomposition_constraints(c(layout.rs1_val_lo), c(layout.rs1_val_hi), &bits_a),
+    ));
+    let is_bitwise = c(layout.is_and) + c(layout.is_or) + c(layout.is_xor);
+    constraints.extend(gate(
+        is_bitwise,
+        CpuAir::bit_decomposition_constraints(c(layout.rs2_val_lo), c(layout.rs2_val_hi), &bits_b),
+    ));
+
+    constraints.extend(gate(
+        c(layout.is_and),
+        CpuAir::bitwise_and_constraints(&bits_a, &bits_b, &bits_result),
+    ));
+    constraints.extend(gate(
+        c(layout.is_or),
+        CpuAir::bitwise_or_constraints(&bits_a, &bits_b, &bits_result),
+    ));
+    constraints.extend(gate(
+        c(layout.is_xor),
+        CpuAir::bitwise_xor_constraints(&bits_a, &bits_b, &bits_result),
+    ));
+
+    let is_shift = c(layout.is_sll) + c(layout.is_srl) + c(layout.is_sra);
+    constraints.extend(gate(
+        is_shift,
+        CpuAir::shift_selector_constraints(&shift_selectors, c(layout.shift_amount), c(layout.pow2)),
+    ));
+    constraints.extend(gate(
+        c(layout.is_sll),
+        CpuAir::shift_left_logical_constraints(&bits_a, &bits_result, &shift_selectors),
+    ));
+    constraints.extend(gate(
+        c(layout.is_srl),
+        CpuAir::shift_right_logical_constraints(&bits_a, &bits_result, &shift_selectors),
+    ));
+    constraints.extend(gate(
+        c(layout.is_sra),
+        CpuAir::shift_right_arithmetic_constraints(&bits_a, &bits_result, &shift_selectors),
+    ));
+
+    constraints.extend(gate(
+        c(layout.is_slt),
+        CpuAir::slt_constraints(
+            rd_val_combined,
+            c(layout.rs1_val_lo),
+            c(layout.rs1_val_hi),
+            c(layout.rs2_val_lo),
+            c(layout.rs2_val_hi),
+            c(layout.sign1),
+            c(layout.sign2),
+            c(layout.diff_lo),
+            c(layout.diff_hi),
+            c(layout.borrow_lo),
+            c(layout.borrow_hi),
+        ),
+    ));
+    constraints.extend(gate(
+        c(layout.is_sltu),
+        CpuAir::sltu_constraints(
+            rd_val_combined,
+            c(layout.rs1_val_lo),
+            c(layout.rs1_val_hi),
+            c(layout.rs2_val_lo),
+            c(layout.rs2_val_hi),
+            c(layout.diff_lo),
+            c(layout.diff_hi),
+            c(layout.borrow_lo),
+            c(layout.borrow_hi),
+        ),
+    ));
+
+    constraints.push(CpuAir::branch_taken_constraint(
+        c(layout.is_beq),
+        c(layout.is_bne),
+        c(layout.is_blt),
+        c(layout.is_bge),
+        c(layout.is_bltu),
+        c(layout.is_bgeu),
+        c(layout.eq_result),
+        c(layout.lt_result),
+        c(layout.ltu_result),
+        c(layout.taken),
+    ));
+    constraints.push(CpuAir::branch_pc_constraint(
+        c(layout.is_beq) + c(layout.is_bne) + c(layout.is_blt) + c(layout.is_bge)
+            + c(layout.is_bltu) + c(layout.is_bgeu),
+        c(layout.pc),
+        c(layout.next_pc),
+        c(layout.imm),
+        c(layout.taken),
+    ));
+
+    let prod: [M31; 4] = core::array::from_fn(|i| c(layout.prod[i]));
+    let mul_carry: [M31; 3] = core::array::from_fn(|i| c(layout.mul_carry[i]));
+    let is_mul_family = c(layout.is_mul)
+        + c(layout.is_mulh)
+        + c(layout.is_mulhu)
+        + c(layout.is_mulhsu)
+        + c(layout.is_div)
+        + c(layout.is_divu)
+        + c(layout.is_rem)
+        + c(layout.is_remu);
+    constraints.extend(gate(
+        is_mul_family,
+        CpuAir::mul_product_constraints(
+            c(layout.rs1_val_lo),
+            c(layout.rs1_val_hi),
+            c(layout.rs2_val_lo),
+            c(layout.rs2_val_hi),
+            prod[0],
+            prod[1],
+            prod[2],
+            prod[3],
+            mul_carry[0],
+            mul_carry[1],
+            mul_carry[2],
+        ),
+    ));
+    constraints.extend(gate(
+        c(layout.is_mul),
+        CpuAir::mul_constraints(c(layout.rd_val_lo), c(layout.rd_val_hi), prod[0], prod[1]),
+    ));
+    constraints.extend(gate(
+        c(layout.is_mulhu),
+        CpuAir::mulhu_constraints(c(layout.rd_val_lo), c(layout.rd_val_hi), prod[2], prod[3]),
+    ));
+    constraints.extend(gate(
+        c(layout.is_mulh),
+        CpuAir::mulh_constraints(
+            c(layout.rd_val_lo),
+            c(layout.rd_val_hi),
+            prod[2],
+            prod[3],
+            c(layout.sign1),
+            c(layout.sign2),
+            rs1_val_combined,
+            rs2_val_combined,
+            c(layout.add_carry),
+        ),
+    ));
+    constraints.extend(gate(
+        c(layout.is_mulhsu),
+        CpuAir::mulhsu_constraints(
+            c(layout.rd_val_lo),
+            c(layout.rd_val_hi),
+            prod[2],
+            prod[3],
+            c(layout.sign1),
+            rs2_val_combined,
+            c(layout.add_carry),
+        ),
+    ));
+
+    let is_div_family = c(layout.is_div) + c(layout.is_divu);
+    constraints.extend(gate(
+        is_div_family,
+        CpuAir::div_constraints(
+            c(layout.rd_val_lo),
+            c(layout.rd_val_hi),
+            c(layout.is_divisor_zero),
+            c(layout.is_signed_overflow),
+            c(layout.quotient_lo),
+            c(layout.quotient_hi),
+            c(layout.rs2_val_lo),
+            c(layout.rs2_val_hi),
+            c(layout.remainder_lo),
+            c(layout.remainder_hi),
+            c(layout.rs1_val_lo),
+            c(layout.rs1_val_hi),
+            prod[0],
+            prod[1],
+            prod[2],
+            prod[3],
+            mul_carry[0],
+            mul_carry[1],
+            mul_carry[2],
+            c(layout.add_carry),
+        ),
+    ));
+    let is_rem_family = c(layout.is_rem) + c(layout.is_remu);
+    constraints.extend(gate(
+        is_rem_family,
+        CpuAir::rem_constraints(
+            c(layout.rd_val_lo),
+            c(layout.rd_val_hi),
+            c(layout.is_divisor_zero),
+            c(layout.is_signed_overflow),
+            c(layout.quotient_lo),
+            c(layout.quotient_hi),
+            c(layout.rs2_val_lo),
+            c(layout.rs2_val_hi),
+            c(layout.remainder_lo),
+            c(layout.remainder_hi),
+            c(layout.rs1_val_lo),
+            c(layout.rs1_val_hi),
+            prod[0],
+            prod[1],
+            prod[2],
+            prod[3],
+            mul_carry[0],
+            mul_carry[1],
+            mul_carry[2],
+            c(layout.add_carry),
+        ),
+    ));
+
+    constraints
+}
+
+/// Evaluate the constraints that link one step row to the next: PC
+/// continuity (the next row's `pc` must be this row's computed
+/// `next_pc`), and the two cross-module QM31 LogUp accumulators
+/// (`mem_perm_acc`, `instr_perm_acc`) this step's memory access and
+/// instruction fetch feed into — see [`CpuAir::logup_lookup_step_ext`] and
+/// [`StepLayout::mem_perm_acc`]/[`StepLayout::instr_perm_acc`]. The table
+/// side of each argument (what the `memory` module and the instruction
+/// ROM lookup table close these accumulators against via
+/// [`CpuAir::logup_close_constraint_ext`]) lives in those modules, not
+/// here.
+pub fn step_transition_constraints(
+    layout: &StepLayout,
+    row: &[M31],
+    next_row: &[M31],
+    mem_challenge: QM31,
+    instr_challenge: QM31,
+) -> Vec<M31> {
+    let c = |i: usize| row[i];
+    let n = |i: usize| next_row[i];
+    let mut constraints = Vec::new();
+
+    constraints.push(n(layout.pc) - c(layout.next_pc));
+
+    let mem_inv = QM31::new(
+        c(layout.mem_perm_inv[0]),
+        c(layout.mem_perm_inv[1]),
+        c(layout.mem_perm_inv[2]),
+        c(layout.mem_perm_inv[3]),
+    );
+    let mem_acc = QM31::new(
+        c(layout.mem_perm_acc[0]),
+        c(layout.mem_perm_acc[1]),
+        c(layout.mem_perm_acc[2]),
+        c(layout.mem_perm_acc[3]),
+    );
+    let mem_acc_next = QM31::new(
+        n(layout.mem_perm_acc[0]),
+        n(layout.mem_perm_acc[1]),
+        n(layout.mem_perm_acc[2]),
+        n(layout.mem_perm_acc[3]),
+    );
+    let is_memory_op = c(layout.is_load) + c(layout.is_store);
+    // The inverse relation only applies on rows that issue a memory access;
+    // on every other row the accumulator must carry over unchanged.
+    let mem_step =
+        CpuAir::logup_lookup_step_ext(mem_challenge, c(layout.mem_record), mem_inv, mem_acc, mem_acc_next);
+    for &cst in &mem_step[..4] {
+        constraints.push(is_memory_op * cst);
+    }
+    let mem_acc_relation = mem_acc_next - mem_acc - QM31::from(is_memory_op) * mem_inv;
+    constraints.extend([mem_acc_relation.c0, mem_acc_relation.c1, mem_acc_relation.c2, mem_acc_relation.c3]);
+
+    let instr_inv = QM31::new(
+        c(layout.instr_perm_inv[0]),
+        c(layout.instr_perm_inv[1]),
+        c(layout.instr_perm_inv[2]),
+        c(layout.instr_perm_inv[3]),
+    );
+    let instr_acc = QM31::new(
+        c(layout.instr_perm_acc[0]),
+        c(layout.instr_perm_acc[1]),
+        c(layout.instr_perm_acc[2]),
+        c(layout.instr_perm_acc[3]),
+    );
+    let instr_acc_next = QM31::new(
+        n(layout.instr_perm_acc[0]),
+        n(layout.instr_perm_acc[1]),
+        n(layout.instr_perm_acc[2]),
+        n(layout.instr_perm_acc[3]),
+    );
+    constraints.extend(CpuAir::logup_lookup_step_ext(
+        instr_challenge,
+        c(layout.instr_record),
+        instr_inv,
+        instr_acc,
+        instr_acc_next,
+    ));
+
+    constraints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper to convert u32 to bit array
+    fn u32_to_bits(value: u32) -> [M31; 32] {
+        let mut bits = [M31::ZERO; 32];
+        for i in 0..32 {
+            bits[i] = if (value >> i) & 1 == 1 {
+                M31::ONE
+            } else {
+                M31::ZERO
+            };
+        }
+        bits
+    }
+
+    /// Helper to split u32 into limbs
+    fn u32_to_limbs(value: u32) -> (M31, M31) {
+        let lo = value & 0xFFFF;
+        let hi = value >> 16;
+        (M31::new(lo), M31::new(hi))
+    }
+
+    #[test]
+    fn test_bit_decomposition_valid() {
+        // Test with value 0x12345678
+        let value = 0x12345678u32;
+        let (lo, hi) = u32_to_limbs(value);
+        let bits = u32_to_bits(value);
+
+        let constraints = CpuAir::bit_decomposition_constraints(lo, hi, &bits);
+        
+        // All 34 constraints should be satisfied (= 0)
+        assert_eq!(constraints.len(), 34);
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "Constraint {} failed", i);
+        }
+    }
+
+    #[test]
+    fn test_bit_decomposition_all_zeros() {
+        let value = 0u32;
+        let (lo, hi) = u32_to_limbs(value);
+        let bits = u32_to_bits(value);
+
+        let constraints = CpuAir::bit_decomposition_constraints(lo, hi, &bits);
+        
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_bit_decomposition_all_ones() {
+        let value = 0xFFFFFFFFu32;
+        let (lo, hi) = u32_to_limbs(value);
+        let bits = u32_to_bits(value);
+
+        let constraints = CpuAir::bit_decomposition_constraints(lo, hi, &bits);
+        
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_bitwise_and_constraint() {
+        // Test: 0b1010 AND 0b1100 = 0b1000
+        let a = 0b1010u32;
+        let b = 0b1100u32;
+        let result = a & b; // = 0b1000
+
+        let bits_a = u32_to_bits(a);
+        let bits_b = u32_to_bits(b);
+        let bits_result = u32_to_bits(result);
+
+        let constraints = CpuAir::bitwise_and_constraints(&bits_a, &bits_b, &bits_result);
+
+        assert_eq!(constraints.len(), 2, "32 one-bit claims should pack into 2 MultiEq groups");
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_bitwise_and_comprehensive() {
+        // Test multiple cases
+        let test_cases = [
+            (0x00000000, 0x00000000, 0x00000000),
+            (0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF),
+            (0xAAAAAAAA, 0x55555555, 0x00000000),
+            (0x12345678, 0xABCDEF00, 0x02044600),
         ];
 
-        for (a, b, expected) in test_cases {
-            let bits_a = u32_to_bits(a);
-            let bits_b = u32_to_bits(b);
-            let bits_result = u32_to_bits(expected);
+        for (a, b, expected) in test_cases {
+            let bits_a = u32_to_bits(a);
+            let bits_b = u32_to_bits(b);
+            let bits_result = u32_to_bits(expected);
+
+            let constraints = CpuAir::bitwise_and_constraints(&bits_a, &bits_b, &bits_result);
+            
+            for (i, constraint) in constraints.iter().enumerate() {
+                assert_eq!(*constraint, M31::ZERO, 
+                    "AND failed for case ({:#x}, {:#x}), constraint {}", a, b, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bitwise_or_constraint() {
+        // Test: 0b1010 OR 0b1100 = 0b1110
+        let a = 0b1010u32;
+        let b = 0b1100u32;
+        let result = a | b; // = 0b1110
+
+        let bits_a = u32_to_bits(a);
+        let bits_b = u32_to_bits(b);
+        let bits_result = u32_to_bits(result);
+
+        let constraints = CpuAir::bitwise_or_constraints(&bits_a, &bits_b, &bits_result);
+
+        assert_eq!(constraints.len(), 2, "32 one-bit claims should pack into 2 MultiEq groups");
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_bitwise_or_comprehensive() {
+        let test_cases = [
+            (0x00000000, 0x00000000, 0x00000000),
+            (0xFFFFFFFF, 0x00000000, 0xFFFFFFFF),
+            (0xAAAAAAAA, 0x55555555, 0xFFFFFFFF),
+            (0x12345678, 0xABCDEF00, 0xBBFDFF78),
+        ];
+
+        for (a, b, expected) in test_cases {
+            let bits_a = u32_to_bits(a);
+            let bits_b = u32_to_bits(b);
+            let bits_result = u32_to_bits(expected);
+
+            let constraints = CpuAir::bitwise_or_constraints(&bits_a, &bits_b, &bits_result);
+            
+            for (i, constraint) in constraints.iter().enumerate() {
+                assert_eq!(*constraint, M31::ZERO,
+                    "OR failed for case ({:#x}, {:#x}), constraint {}", a, b, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bitwise_xor_constraint() {
+        // Test: 0b1010 XOR 0b1100 = 0b0110
+        let a = 0b1010u32;
+        let b = 0b1100u32;
+        let result = a ^ b; // = 0b0110
+
+        let bits_a = u32_to_bits(a);
+        let bits_b = u32_to_bits(b);
+        let bits_result = u32_to_bits(result);
+
+        let constraints = CpuAir::bitwise_xor_constraints(&bits_a, &bits_b, &bits_result);
+
+        assert_eq!(constraints.len(), 2, "32 one-bit claims should pack into 2 MultiEq groups");
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_bitwise_xor_comprehensive() {
+        let test_cases = [
+            (0x00000000, 0x00000000, 0x00000000),
+            (0xFFFFFFFF, 0xFFFFFFFF, 0x00000000),
+            (0xAAAAAAAA, 0x55555555, 0xFFFFFFFF),
+            (0x12345678, 0xABCDEF00, 0xB9F9B978),
+        ];
+
+        for (a, b, expected) in test_cases {
+            let bits_a = u32_to_bits(a);
+            let bits_b = u32_to_bits(b);
+            let bits_result = u32_to_bits(expected);
+
+            let constraints = CpuAir::bitwise_xor_constraints(&bits_a, &bits_b, &bits_result);
+            
+            for (i, constraint) in constraints.iter().enumerate() {
+                assert_eq!(*constraint, M31::ZERO,
+                    "XOR failed for case ({:#x}, {:#x}), constraint {}", a, b, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bitwise_and_soundness() {
+        // Test that wrong result fails constraint
+        let a = 0xAAAAu32;
+        let b = 0x5555u32;
+        let wrong_result = 0xFFFFu32; // Should be 0x0000
+
+        let bits_a = u32_to_bits(a);
+        let bits_b = u32_to_bits(b);
+        let bits_wrong = u32_to_bits(wrong_result);
+
+        let constraints = CpuAir::bitwise_and_constraints(&bits_a, &bits_b, &bits_wrong);
+        
+        // Should have non-zero constraints
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "Constraint should catch incorrect AND result");
+    }
+
+    #[test]
+    fn test_bit_decomposition_soundness() {
+        // Test that incorrect bit decomposition fails
+        let value = 0x12345678u32;
+        let (lo, hi) = u32_to_limbs(value);
+        let mut bits = u32_to_bits(value);
+        
+        // Flip a bit
+        bits[5] = if bits[5] == M31::ZERO { M31::ONE } else { M31::ZERO };
+
+        let constraints = CpuAir::bit_decomposition_constraints(lo, hi, &bits);
+        
+        // Should have non-zero constraints (reconstruction will fail)
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "Constraint should catch incorrect bit decomposition");
+    }
+
+    #[test]
+    fn test_shift_left_logical() {
+        // Test SLL: 0b1010 << 1 = 0b10100
+        let value = 0b1010u32;
+        let shift = 1u32;
+        let expected = value << shift;
+
+        let bits_value = u32_to_bits(value);
+        let bits_result = u32_to_bits(expected);
+        let selectors = CpuAir::shift_selectors(shift);
+
+        let constraints = CpuAir::shift_left_logical_constraints(
+            &bits_value,
+            &bits_result,
+            &selectors,
+        );
+
+        assert_eq!(constraints.len(), 32);
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "SLL constraint {} failed", i);
+        }
+    }
+
+    #[test]
+    fn test_shift_left_comprehensive() {
+        let test_cases = [
+            (0x00000001, 0, 0x00000001),  // No shift
+            (0x00000001, 1, 0x00000002),  // Simple shift
+            (0x00000001, 31, 0x80000000), // Shift to MSB
+            (0xFFFFFFFF, 1, 0xFFFFFFFE),  // All ones
+            (0x12345678, 4, 0x23456780),  // Nibble shift
+            (0x00000001, 32, 0x00000001), // Shift by 32 (wraps to 0)
+        ];
+
+        for (value, shift, expected) in test_cases {
+            let bits_value = u32_to_bits(value);
+            let bits_result = u32_to_bits(expected);
+            let selectors = CpuAir::shift_selectors(shift);
+
+            let constraints = CpuAir::shift_left_logical_constraints(
+                &bits_value,
+                &bits_result,
+                &selectors,
+            );
+
+            for (i, constraint) in constraints.iter().enumerate() {
+                assert_eq!(
+                    *constraint, M31::ZERO,
+                    "SLL({:#x} << {}) failed at bit {}", value, shift, i
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_right_logical() {
+        // Test SRL: 0b1010 >> 1 = 0b0101
+        let value = 0b1010u32;
+        let shift = 1u32;
+        let expected = value >> shift;
+
+        let bits_value = u32_to_bits(value);
+        let bits_result = u32_to_bits(expected);
+        let selectors = CpuAir::shift_selectors(shift);
+
+        let constraints = CpuAir::shift_right_logical_constraints(
+            &bits_value,
+            &bits_result,
+            &selectors,
+        );
+
+        assert_eq!(constraints.len(), 32);
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_shift_right_logical_comprehensive() {
+        let test_cases = [
+            (0x80000000, 0, 0x80000000),  // No shift
+            (0x80000000, 1, 0x40000000),  // Shift MSB
+            (0x80000000, 31, 0x00000001), // Shift to LSB
+            (0xFFFFFFFF, 1, 0x7FFFFFFF),  // Zero-extend from left
+            (0x12345678, 4, 0x01234567),  // Nibble shift
+            (0x80000000, 32, 0x80000000), // Shift by 32 (wraps to 0)
+        ];
+
+        for (value, shift, expected) in test_cases {
+            let bits_value = u32_to_bits(value);
+            let bits_result = u32_to_bits(expected);
+            let selectors = CpuAir::shift_selectors(shift);
+
+            let constraints = CpuAir::shift_right_logical_constraints(
+                &bits_value,
+                &bits_result,
+                &selectors,
+            );
+
+            for (i, constraint) in constraints.iter().enumerate() {
+                assert_eq!(
+                    *constraint, M31::ZERO,
+                    "SRL({:#x} >> {}) failed at bit {}", value, shift, i
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_right_arithmetic() {
+        // Test SRA with positive number (MSB = 0)
+        let value = 0b01010u32;
+        let shift = 1u32;
+        let expected = value >> shift; // 0b00101
+
+        let bits_value = u32_to_bits(value);
+        let bits_result = u32_to_bits(expected);
+        let selectors = CpuAir::shift_selectors(shift);
+
+        let constraints = CpuAir::shift_right_arithmetic_constraints(
+            &bits_value,
+            &bits_result,
+            &selectors,
+        );
+
+        assert_eq!(constraints.len(), 32);
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_shift_right_arithmetic_negative() {
+        // Test SRA with negative number (MSB = 1) - sign extension
+        let value = 0x80000000u32; // Negative in two's complement
+        let shift = 1u32;
+        let expected = 0xC0000000u32; // Sign-extended: 1100...
+
+        let bits_value = u32_to_bits(value);
+        let bits_result = u32_to_bits(expected);
+        let selectors = CpuAir::shift_selectors(shift);
+
+        let constraints = CpuAir::shift_right_arithmetic_constraints(
+            &bits_value,
+            &bits_result,
+            &selectors,
+        );
+
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO, "SRA sign extension failed");
+        }
+    }
+
+    #[test]
+    fn test_shift_right_arithmetic_comprehensive() {
+        let test_cases = [
+            // (value, shift, expected_sra)
+            (0x00000008, 1, 0x00000004),  // Positive: 8 >> 1 = 4
+            (0x00000008, 2, 0x00000002),  // Positive: 8 >> 2 = 2
+            (0xFFFFFFF8u32, 1, 0xFFFFFFFCu32), // Negative: -8 >> 1 = -4 (sign extend)
+            (0xFFFFFFF8u32, 2, 0xFFFFFFFEu32), // Negative: -8 >> 2 = -2 (sign extend)
+            (0x80000000u32, 31, 0xFFFFFFFFu32), // Min int >> 31 = -1 (all ones)
+            (0x7FFFFFFF, 31, 0x00000000),  // Max int >> 31 = 0
+        ];
+
+        for (value, shift, expected) in test_cases {
+            let bits_value = u32_to_bits(value);
+            let bits_result = u32_to_bits(expected);
+            let selectors = CpuAir::shift_selectors(shift);
+
+            let constraints = CpuAir::shift_right_arithmetic_constraints(
+                &bits_value,
+                &bits_result,
+                &selectors,
+            );
+
+            for (i, constraint) in constraints.iter().enumerate() {
+                assert_eq!(
+                    *constraint, M31::ZERO,
+                    "SRA({:#x} >> {}) failed at bit {}, expected {:#x}",
+                    value, shift, i, expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_soundness() {
+        // Test that wrong shift result fails constraint
+        let value = 0x12345678u32;
+        let shift = 4u32;
+        let wrong_result = 0x23456781u32; // Should be 0x23456780
+
+        let bits_value = u32_to_bits(value);
+        let bits_wrong = u32_to_bits(wrong_result);
+        let selectors = CpuAir::shift_selectors(shift);
+
+        let constraints = CpuAir::shift_left_logical_constraints(
+            &bits_value,
+            &bits_wrong,
+            &selectors,
+        );
+
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "Constraint should catch incorrect shift result");
+    }
+
+    #[test]
+    fn test_shift_selector_constraints_valid_selector() {
+        let shift = 13u32;
+        let selectors = CpuAir::shift_selectors(shift);
+        let pow2 = M31::new(1 << shift);
+
+        let constraints =
+            CpuAir::shift_selector_constraints(&selectors, M31::new(shift), pow2);
+
+        assert_eq!(constraints.len(), 35);
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "selector constraint {} failed", i);
+        }
+    }
+
+    #[test]
+    fn test_shift_selector_constraints_reject_non_boolean_selector() {
+        let mut selectors = CpuAir::shift_selectors(5);
+        selectors[5] = M31::new(2); // not a valid boolean selector
+
+        let constraints =
+            CpuAir::shift_selector_constraints(&selectors, M31::new(5), M31::new(1 << 5));
+
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "Constraint should catch a non-boolean selector");
+    }
+
+    #[test]
+    fn test_shift_selector_constraints_reject_shift_amount_mismatch() {
+        // Selectors pick k=5, but the claimed shift_amount says 6: nothing
+        // in the naive per-bit formula alone would catch this, which is
+        // exactly the soundness gap the selector-sum/weighted-sum binding
+        // closes.
+        let selectors = CpuAir::shift_selectors(5);
+
+        let constraints =
+            CpuAir::shift_selector_constraints(&selectors, M31::new(6), M31::new(1 << 5));
+
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "Constraint should catch a shift_amount/selector mismatch");
+    }
+
+    #[test]
+    fn test_shift_selector_constraints_reject_no_selector_set() {
+        // All-zero selectors satisfy every booleanity constraint but not
+        // the sum-to-one constraint, so a prover can't just skip selecting
+        // a shift to dodge the per-bit formula.
+        let selectors = [M31::ZERO; 32];
+
+        let constraints =
+            CpuAir::shift_selector_constraints(&selectors, M31::ZERO, M31::ZERO);
+
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "Constraint should catch an unset selector set");
+    }
+
+    #[test]
+    fn test_shift_result_soundness_with_mismatched_selector() {
+        // A malicious prover claims shift=4's result while the selectors
+        // are actually bound (via shift_selector_constraints) to shift=5 —
+        // shift_left_logical_constraints must reject the mismatch, unlike
+        // the old implementation that only ever looked at a concrete
+        // Rust-side shift_amount and couldn't be fooled this way only
+        // because it never bound one to the witness at all.
+        let value = 0x0000_0001u32;
+        let claimed_shift = 4u32;
+        let actual_selectors = CpuAir::shift_selectors(5);
+
+        let bits_value = u32_to_bits(value);
+        let bits_result = u32_to_bits(value << claimed_shift);
+
+        let constraints = CpuAir::shift_left_logical_constraints(
+            &bits_value,
+            &bits_result,
+            &actual_selectors,
+        );
+
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "Constraint should catch a result computed with the wrong shift");
+    }
+
+    /// Helper: compute the correct `mul_product_constraints` witnesses
+    /// (the product's 4 limbs plus its 3 carries) for concrete operands.
+    fn mul_witness(rs1: u32, rs2: u32) -> (M31, M31, M31, M31, M31, M31, M31) {
+        let (rs1_lo, rs1_hi) = u32_to_limbs(rs1);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(rs2);
+        let product = (rs1 as u64) * (rs2 as u64);
+
+        let prod_0 = M31::new((product & 0xFFFF) as u32);
+        let prod_1 = M31::new(((product >> 16) & 0xFFFF) as u32);
+        let prod_2 = M31::new(((product >> 32) & 0xFFFF) as u32);
+        let prod_3 = M31::new(((product >> 48) & 0xFFFF) as u32);
+
+        let raw_0 = (rs1_lo.value() as u64) * (rs2_lo.value() as u64);
+        let carry_0 = M31::new((raw_0 >> 16) as u32);
+        let raw_1 = (rs1_lo.value() as u64) * (rs2_hi.value() as u64)
+            + (rs1_hi.value() as u64) * (rs2_lo.value() as u64)
+            + carry_0.value() as u64;
+        let carry_1 = M31::new((raw_1 >> 16) as u32);
+        let raw_2 = (rs1_hi.value() as u64) * (rs2_hi.value() as u64) + carry_1.value() as u64;
+        let carry_2 = M31::new((raw_2 >> 16) as u32);
+
+        (prod_0, prod_1, prod_2, prod_3, carry_0, carry_1, carry_2)
+    }
+
+    #[test]
+    fn test_mul_product_constraints_valid() {
+        let (rs1_lo, rs1_hi) = u32_to_limbs(0x12345678);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(0x9ABCDEF0);
+        let (prod_0, prod_1, prod_2, prod_3, c0, c1, c2) = mul_witness(0x12345678, 0x9ABCDEF0);
+
+        let constraints = CpuAir::mul_product_constraints(
+            rs1_lo, rs1_hi, rs2_lo, rs2_hi, prod_0, prod_1, prod_2, prod_3, c0, c1, c2,
+        );
+
+        assert_eq!(constraints.len(), 4);
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_mul_constraints_low_bits() {
+        let rs1 = 0xFFFFFFFFu32;
+        let rs2 = 0xFFFFFFFFu32;
+        let expected = rs1.wrapping_mul(rs2); // 0x00000001
+        let (lo, hi) = u32_to_limbs(expected);
+        let (prod_0, prod_1, ..) = mul_witness(rs1, rs2);
+
+        let constraints = CpuAir::mul_constraints(lo, hi, prod_0, prod_1);
+
+        assert_eq!(constraints.len(), 2);
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_mulhu_constraints() {
+        let rs1 = 0xFFFFFFFFu32;
+        let rs2 = 0xFFFFFFFFu32;
+        let expected_high = ((rs1 as u64 * rs2 as u64) >> 32) as u32; // 0xFFFFFFFE
+        let (lo, hi) = u32_to_limbs(expected_high);
+        let (_, _, prod_2, prod_3, ..) = mul_witness(rs1, rs2);
+
+        let constraints = CpuAir::mulhu_constraints(lo, hi, prod_2, prod_3);
+
+        assert_eq!(constraints.len(), 2);
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_mulh_constraints_negative_operands() {
+        // -2 * -3 = 6, so MULH's high word is 0.
+        let rs1 = (-2i32) as u32;
+        let rs2 = (-3i32) as u32;
+        let (_, _, prod_2, prod_3, ..) = mul_witness(rs1, rs2);
+        let rs1_val = M31::new(rs1);
+        let rs2_val = M31::new(rs2);
+
+        let constraints = CpuAir::mulh_constraints(
+            M31::ZERO,
+            M31::ZERO,
+            prod_2,
+            prod_3,
+            M31::ONE, // sign1
+            M31::ONE, // sign2
+            rs1_val,
+            rs2_val,
+            M31::ONE, // borrow
+        );
+
+        assert_eq!(constraints.len(), 1);
+        assert_eq!(constraints[0], M31::ZERO);
+    }
+
+    #[test]
+    fn test_mulhsu_constraints_negative_rs1() {
+        // -2 (signed) * 3 (unsigned) = -6, high word of the 64-bit
+        // two's-complement result is all ones.
+        let rs1 = (-2i32) as u32;
+        let rs2 = 3u32;
+        let (_, _, prod_2, prod_3, ..) = mul_witness(rs1, rs2);
+        let (lo, hi) = u32_to_limbs(0xFFFFFFFFu32);
+        let rs2_val = M31::new(rs2);
+
+        let constraints = CpuAir::mulhsu_constraints(
+            lo,
+            hi,
+            prod_2,
+            prod_3,
+            M31::ONE, // sign1
+            rs2_val,
+            M31::ONE, // borrow
+        );
+
+        assert_eq!(constraints.len(), 1);
+        assert_eq!(constraints[0], M31::ZERO);
+    }
+
+    #[test]
+    fn test_mul_soundness() {
+        let (rs1_lo, rs1_hi) = u32_to_limbs(0x12345678);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(0x9ABCDEF0);
+        let (prod_0, prod_1, prod_2, prod_3, c0, c1, c2) = mul_witness(0x12345678, 0x9ABCDEF0);
+        let wrong_prod_0 = prod_0 + M31::ONE;
+
+        let constraints = CpuAir::mul_product_constraints(
+            rs1_lo, rs1_hi, rs2_lo, rs2_hi, wrong_prod_0, prod_1, prod_2, prod_3, c0, c1, c2,
+        );
+
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "Constraint should catch an incorrect product limb");
+    }
+
+    /// Helper: compute the correct `div_rem_identity_constraints`
+    /// witnesses (quotient/remainder limbs plus the product/sum carries)
+    /// for a concrete unsigned dividend/divisor pair.
+    #[allow(clippy::type_complexity)]
+    fn div_rem_witness(
+        dividend: u32,
+        divisor: u32,
+    ) -> (
+        M31, M31, M31, M31, M31, M31, M31, M31, M31, M31, M31, M31, M31, M31, M31, M31,
+    ) {
+        let quotient = dividend / divisor;
+        let remainder = dividend % divisor;
+
+        let (quotient_lo, quotient_hi) = u32_to_limbs(quotient);
+        let (divisor_lo, divisor_hi) = u32_to_limbs(divisor);
+        let (remainder_lo, remainder_hi) = u32_to_limbs(remainder);
+        let (dividend_lo, dividend_hi) = u32_to_limbs(dividend);
+        let (prod_0, prod_1, prod_2, prod_3, mc0, mc1, mc2) = mul_witness(quotient, divisor);
+
+        let raw_sum = prod_0.value() as u64 + remainder_lo.value() as u64;
+        let add_carry = M31::new((raw_sum >> 16) as u32);
+
+        (
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mc0,
+            mc1,
+            mc2,
+            add_carry,
+        )
+    }
+
+    #[test]
+    fn test_div_rem_identity_valid() {
+        let (
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mc0,
+            mc1,
+            mc2,
+            add_carry,
+        ) = div_rem_witness(17, 5);
+
+        let constraints = CpuAir::div_rem_identity_constraints(
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mc0,
+            mc1,
+            mc2,
+            add_carry,
+        );
+
+        assert_eq!(constraints.len(), 8);
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "identity constraint {} failed", i);
+        }
+    }
+
+    #[test]
+    fn test_div_edge_case_divisor_zero() {
+        let (dividend_lo, dividend_hi) = u32_to_limbs(42);
+        let (all_ones_lo, all_ones_hi) = u32_to_limbs(0xFFFFFFFF);
+
+        let constraints = CpuAir::div_edge_case_constraints(
+            M31::ONE,  // is_divisor_zero
+            M31::ZERO, // is_signed_overflow
+            all_ones_lo,
+            all_ones_hi,
+            dividend_lo,
+            dividend_hi,
+            dividend_lo,
+            dividend_hi,
+        );
+
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_div_edge_case_signed_overflow() {
+        let (quotient_lo, quotient_hi) = u32_to_limbs(0x80000000); // INT_MIN
+        let (dividend_lo, dividend_hi) = u32_to_limbs(0x80000000);
+
+        let constraints = CpuAir::div_edge_case_constraints(
+            M31::ZERO, // is_divisor_zero
+            M31::ONE,  // is_signed_overflow
+            quotient_lo,
+            quotient_hi,
+            M31::ZERO, // remainder_lo
+            M31::ZERO, // remainder_hi
+            dividend_lo,
+            dividend_hi,
+        );
+
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_div_constraints_binds_quotient() {
+        let (
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mc0,
+            mc1,
+            mc2,
+            add_carry,
+        ) = div_rem_witness(17, 5);
+
+        let constraints = CpuAir::div_constraints(
+            quotient_lo,
+            quotient_hi,
+            M31::ZERO,
+            M31::ZERO,
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mc0,
+            mc1,
+            mc2,
+            add_carry,
+        );
+
+        assert_eq!(constraints.len(), 18);
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_rem_constraints_binds_remainder() {
+        let (
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mc0,
+            mc1,
+            mc2,
+            add_carry,
+        ) = div_rem_witness(17, 5);
+
+        let constraints = CpuAir::rem_constraints(
+            remainder_lo,
+            remainder_hi,
+            M31::ZERO,
+            M31::ZERO,
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mc0,
+            mc1,
+            mc2,
+            add_carry,
+        );
+
+        assert_eq!(constraints.len(), 18);
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_div_soundness_wrong_quotient() {
+        let (
+            quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mc0,
+            mc1,
+            mc2,
+            add_carry,
+        ) = div_rem_witness(17, 5);
+        let wrong_quotient_lo = quotient_lo + M31::ONE; // claims quotient 4 instead of 3
+
+        let constraints = CpuAir::div_constraints(
+            wrong_quotient_lo,
+            quotient_hi,
+            M31::ZERO,
+            M31::ZERO,
+            wrong_quotient_lo,
+            quotient_hi,
+            divisor_lo,
+            divisor_hi,
+            remainder_lo,
+            remainder_hi,
+            dividend_lo,
+            dividend_hi,
+            prod_0,
+            prod_1,
+            prod_2,
+            prod_3,
+            mc0,
+            mc1,
+            mc2,
+            add_carry,
+        );
+
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "Constraint should catch an incorrect quotient");
+    }
+
+    #[test]
+    fn test_logup_lookup_step_valid() {
+        let x = M31::new(100);
+        let value = M31::new(42);
+        let inv = (x - value).inverse();
+        let acc = M31::new(7);
+        let acc_next = acc + inv;
+
+        let constraints = CpuAir::logup_lookup_step(x, value, inv, acc, acc_next);
+
+        assert_eq!(constraints.len(), 2);
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_logup_lookup_step_rejects_wrong_inverse() {
+        let x = M31::new(100);
+        let value = M31::new(42);
+        let wrong_inv = M31::new(1); // not 1/(x - value)
+        let acc = M31::new(7);
+        let acc_next = acc + wrong_inv;
+
+        let constraints = CpuAir::logup_lookup_step(x, value, wrong_inv, acc, acc_next);
+
+        assert_ne!(constraints[0], M31::ZERO, "bogus inverse witness should be caught");
+    }
+
+    #[test]
+    fn test_logup_lookup_step_rejects_accumulator_mismatch() {
+        let x = M31::new(100);
+        let value = M31::new(42);
+        let inv = (x - value).inverse();
+        let acc = M31::new(7);
+        let wrong_acc_next = acc + inv + M31::ONE; // didn't fold inv in correctly
+
+        let constraints = CpuAir::logup_lookup_step(x, value, inv, acc, wrong_acc_next);
+
+        assert_ne!(constraints[1], M31::ZERO, "accumulator update mismatch should be caught");
+    }
+
+    #[test]
+    fn test_logup_table_step_valid() {
+        let x = M31::new(100);
+        let table_entry = M31::new(42);
+        let multiplicity = M31::new(3);
+        let inv = (x - table_entry).inverse();
+        let acc = M31::new(7);
+        let acc_next = acc + multiplicity * inv;
+
+        let constraints = CpuAir::logup_table_step(x, table_entry, multiplicity, inv, acc, acc_next);
+
+        assert_eq!(constraints.len(), 2);
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_logup_close_constraint() {
+        let total = M31::new(12345);
+        assert_eq!(CpuAir::logup_close_constraint(total, total), M31::ZERO);
+        assert_ne!(CpuAir::logup_close_constraint(total, total + M31::ONE), M31::ZERO);
+    }
+
+    #[test]
+    fn test_range_check_16_valid_value() {
+        let x = M31::new(999);
+        let value = M31::new(0xFFFF); // top of the valid 16-bit range
+        let inv = (x - value).inverse();
+        let acc = M31::ZERO;
+        let acc_next = acc + inv;
+
+        let constraints = CpuAir::range_check_16(x, value, inv, acc, acc_next);
+
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_range_check_bit_accepts_zero_and_one() {
+        assert_eq!(CpuAir::range_check_bit(M31::ZERO), M31::ZERO);
+        assert_eq!(CpuAir::range_check_bit(M31::ONE), M31::ZERO);
+    }
+
+    #[test]
+    fn test_range_check_bit_rejects_other_values() {
+        assert_ne!(CpuAir::range_check_bit(M31::new(2)), M31::ZERO);
+        assert_ne!(CpuAir::range_check_bit(M31::new(0xFFFF)), M31::ZERO);
+    }
+
+    #[test]
+    fn test_add_range_checked_constraints_valid() {
+        let (rs1_lo, rs1_hi) = u32_to_limbs(0x0000FFFF);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(0x00000002);
+        let (rd_lo, rd_hi) = u32_to_limbs(0x00010001);
+        let carry = M31::ONE;
+        let x = M31::new(777);
+        let lo_inv = (x - rd_lo).inverse();
+        let hi_inv = (x - rd_hi).inverse();
+        let lo_acc = M31::ZERO;
+        let hi_acc = M31::ZERO;
+
+        let constraints = CpuAir::add_range_checked_constraints(
+            M31::ONE,
+            rd_lo,
+            rd_hi,
+            rs1_lo,
+            rs1_hi,
+            rs2_lo,
+            rs2_hi,
+            carry,
+            x,
+            lo_inv,
+            lo_acc,
+            lo_acc + lo_inv,
+            hi_inv,
+            hi_acc,
+            hi_acc + hi_inv,
+        );
+
+        assert_eq!(constraints.len(), 7);
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "constraint {} failed", i);
+        }
+    }
+
+    #[test]
+    fn test_add_range_checked_constraints_rejects_non_binary_carry() {
+        let (rs1_lo, rs1_hi) = u32_to_limbs(0x0000FFFF);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(0x00000002);
+        let (rd_lo, rd_hi) = u32_to_limbs(0x00010001);
+        let bogus_carry = M31::new(2); // not binary
+        let x = M31::new(777);
+        let lo_inv = (x - rd_lo).inverse();
+        let hi_inv = (x - rd_hi).inverse();
+
+        let constraints = CpuAir::add_range_checked_constraints(
+            M31::ONE,
+            rd_lo,
+            rd_hi,
+            rs1_lo,
+            rs1_hi,
+            rs2_lo,
+            rs2_hi,
+            bogus_carry,
+            x,
+            lo_inv,
+            M31::ZERO,
+            lo_inv,
+            hi_inv,
+            M31::ZERO,
+            hi_inv,
+        );
+
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "Constraint should catch a non-binary carry witness");
+    }
+
+    #[test]
+    fn test_mul_range_checked_constraints_valid() {
+        // 0xFFFFFFFF * 0xFFFFFFFF drives carry_1 to 131069, past 2^16 —
+        // the case `range_check_bit` used to wrongly reject.
+        let (rs1_lo, rs1_hi) = u32_to_limbs(0xFFFFFFFF);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(0xFFFFFFFF);
+        let (prod_0, prod_1, prod_2, prod_3, c0, c1, c2) = mul_witness(0xFFFFFFFF, 0xFFFFFFFF);
+        let c1_lo = M31::new(c1.value() & 0xFFFF);
+        let c1_hi = M31::new(c1.value() >> 16);
+        assert_eq!(c1_hi, M31::ONE, "this case should exercise carry_1's 17th bit");
+        let x = M31::new(555);
+
+        let values = [prod_0, prod_1, prod_2, prod_3, c0, c1_lo, c2];
+        let invs = values.map(|v| (x - v).inverse());
+        let accs = [M31::ZERO; 7];
+        let accs_next = [0, 1, 2, 3, 4, 5, 6].map(|i: usize| accs[i] + invs[i]);
+
+        let constraints = CpuAir::mul_range_checked_constraints(
+            rs1_lo, rs1_hi, rs2_lo, rs2_hi, prod_0, prod_1, prod_2, prod_3, c0, c1, c2, c1_lo,
+            c1_hi, x, invs, accs, accs_next,
+        );
+
+        assert_eq!(constraints.len(), 20);
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "constraint {} failed", i);
+        }
+    }
+
+    #[test]
+    fn test_mul_range_checked_constraints_accepts_non_binary_carry_0() {
+        // 512 * 512 = 262144, whose low-limb partial product already
+        // gives carry_0 = 4: a legitimate witness for an ordinary MUL
+        // that `range_check_bit(carry_0)` would have rejected outright.
+        let (rs1_lo, rs1_hi) = u32_to_limbs(512);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(512);
+        let (prod_0, prod_1, prod_2, prod_3, c0, c1, c2) = mul_witness(512, 512);
+        assert_eq!(c0, M31::new(4));
+        let c1_lo = M31::new(c1.value() & 0xFFFF);
+        let c1_hi = M31::new(c1.value() >> 16);
+        let x = M31::new(777);
+
+        let values = [prod_0, prod_1, prod_2, prod_3, c0, c1_lo, c2];
+        let invs = values.map(|v| (x - v).inverse());
+        let accs = [M31::ZERO; 7];
+        let accs_next = [0, 1, 2, 3, 4, 5, 6].map(|i: usize| accs[i] + invs[i]);
+
+        let constraints = CpuAir::mul_range_checked_constraints(
+            rs1_lo, rs1_hi, rs2_lo, rs2_hi, prod_0, prod_1, prod_2, prod_3, c0, c1, c2, c1_lo,
+            c1_hi, x, invs, accs, accs_next,
+        );
+
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "constraint {} failed", i);
+        }
+    }
+
+    #[test]
+    fn test_boolean_booleanity() {
+        assert_eq!(Boolean::new(M31::ZERO).booleanity_constraint(), M31::ZERO);
+        assert_eq!(Boolean::new(M31::ONE).booleanity_constraint(), M31::ZERO);
+        assert_ne!(Boolean::new(M31::new(2)).booleanity_constraint(), M31::ZERO);
+    }
+
+    #[test]
+    fn test_boolean_gates() {
+        let t = Boolean::new(M31::ONE);
+        let f = Boolean::new(M31::ZERO);
+
+        assert_eq!(t.and(f).value(), M31::ZERO);
+        assert_eq!(t.and(t).value(), M31::ONE);
+        assert_eq!(f.or(f).value(), M31::ZERO);
+        assert_eq!(t.or(f).value(), M31::ONE);
+        assert_eq!(t.xor(t).value(), M31::ZERO);
+        assert_eq!(t.xor(f).value(), M31::ONE);
+        assert_eq!(t.not().value(), M31::ZERO);
+        assert_eq!(f.not().value(), M31::ONE);
+    }
+
+    #[test]
+    fn test_multieq_packs_claims_within_capacity() {
+        let mut eq = MultiEq::new();
+        eq.push(M31::new(5), M31::new(5), 16);
+        eq.push(M31::new(7), M31::new(7), 8);
+
+        // 16 + 8 = 24 fits in one group (MULTIEQ_CAPACITY_BITS), so only
+        // `finish`'s trailing flush produces a constraint.
+        let constraints = eq.finish();
+        assert_eq!(constraints.len(), 1);
+        assert_eq!(constraints[0], M31::ZERO);
+    }
+
+    #[test]
+    fn test_multieq_flushes_before_overflowing_capacity() {
+        let mut eq = MultiEq::new();
+        eq.push(M31::new(1), M31::new(1), 16);
+        eq.push(M31::new(2), M31::new(2), 16); // would overflow 24 bits, forces a flush first
+        let constraints = eq.finish();
+
+        assert_eq!(constraints.len(), 2);
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_multieq_rejects_mismatched_claim() {
+        let mut eq = MultiEq::new();
+        eq.push(M31::new(5), M31::new(6), 16); // lhs != rhs
+        let constraints = eq.finish();
+
+        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "MultiEq should catch a mismatched packed claim");
+    }
+
+    /// Helper: compute the correct `borrow_chain_constraints` witnesses
+    /// (both diff limbs and both borrows) for concrete unsigned operands.
+    fn borrow_witness(rs1: u32, rs2: u32) -> (M31, M31, M31, M31) {
+        let (rs1_lo, rs1_hi) = u32_to_limbs(rs1);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(rs2);
+
+        let borrow_lo = if rs1_lo.value() < rs2_lo.value() { 1 } else { 0 };
+        let diff_lo = M31::new(rs1_lo.value().wrapping_sub(rs2_lo.value()).wrapping_add(borrow_lo << 16));
+
+        let borrow_hi = if rs1_hi.value().wrapping_sub(borrow_lo) < rs2_hi.value() {
+            1
+        } else {
+            0
+        };
+        let diff_hi = M31::new(
+            rs1_hi
+                .value()
+                .wrapping_sub(rs2_hi.value())
+                .wrapping_sub(borrow_lo)
+                .wrapping_add(borrow_hi << 16),
+        );
+
+        (diff_lo, diff_hi, M31::new(borrow_lo), M31::new(borrow_hi))
+    }
+
+    #[test]
+    fn test_sltu_constraints_less_than() {
+        let (rs1_lo, rs1_hi) = u32_to_limbs(3);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(5);
+        let (diff_lo, diff_hi, borrow_lo, borrow_hi) = borrow_witness(3, 5);
 
-            let constraints = CpuAir::bitwise_or_constraints(&bits_a, &bits_b, &bits_result);
-            
-            for (i, constraint) in constraints.iter().enumerate() {
-                assert_eq!(*constraint, M31::ZERO,
-                    "OR failed for case ({:#x}, {:#x}), bit {}", a, b, i);
-            }
+        let constraints = CpuAir::sltu_constraints(
+            M31::ONE, rs1_lo, rs1_hi, rs2_lo, rs2_hi, diff_lo, diff_hi, borrow_lo, borrow_hi,
+        );
+
+        assert_eq!(constraints.len(), 5);
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "constraint {} failed", i);
         }
     }
 
     #[test]
-    fn test_bitwise_xor_constraint() {
-        // Test: 0b1010 XOR 0b1100 = 0b0110
-        let a = 0b1010u32;
-        let b = 0b1100u32;
-        let result = a ^ b; // = 0b0110
+    fn test_sltu_constraints_not_less_than() {
+        let (rs1_lo, rs1_hi) = u32_to_limbs(10);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(5);
+        let (diff_lo, diff_hi, borrow_lo, borrow_hi) = borrow_witness(10, 5);
 
-        let bits_a = u32_to_bits(a);
-        let bits_b = u32_to_bits(b);
-        let bits_result = u32_to_bits(result);
+        let constraints = CpuAir::sltu_constraints(
+            M31::ZERO, rs1_lo, rs1_hi, rs2_lo, rs2_hi, diff_lo, diff_hi, borrow_lo, borrow_hi,
+        );
 
-        let constraints = CpuAir::bitwise_xor_constraints(&bits_a, &bits_b, &bits_result);
-        
-        assert_eq!(constraints.len(), 32);
         for constraint in constraints {
             assert_eq!(constraint, M31::ZERO);
         }
     }
 
     #[test]
-    fn test_bitwise_xor_comprehensive() {
-        let test_cases = [
-            (0x00000000, 0x00000000, 0x00000000),
-            (0xFFFFFFFF, 0xFFFFFFFF, 0x00000000),
-            (0xAAAAAAAA, 0x55555555, 0xFFFFFFFF),
-            (0x12345678, 0xABCDEF00, 0xB9F9B978),
-        ];
-
-        for (a, b, expected) in test_cases {
-            let bits_a = u32_to_bits(a);
-            let bits_b = u32_to_bits(b);
-            let bits_result = u32_to_bits(expected);
+    fn test_slt_constraints_negative_operands() {
+        // -3 < -2 is true
+        let rs1 = (-3i32) as u32;
+        let rs2 = (-2i32) as u32;
+        let (rs1_lo, rs1_hi) = u32_to_limbs(rs1);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(rs2);
+        let sign1 = M31::ONE;
+        let sign2 = M31::ONE;
+
+        let sign_flip = 0x8000u32;
+        let flipped_rs1 = rs1 ^ (sign_flip << 16);
+        let flipped_rs2 = rs2 ^ (sign_flip << 16);
+        let (diff_lo, diff_hi, borrow_lo, borrow_hi) = borrow_witness(flipped_rs1, flipped_rs2);
+
+        let constraints = CpuAir::slt_constraints(
+            M31::ONE, rs1_lo, rs1_hi, rs2_lo, rs2_hi, sign1, sign2, diff_lo, diff_hi, borrow_lo,
+            borrow_hi,
+        );
 
-            let constraints = CpuAir::bitwise_xor_constraints(&bits_a, &bits_b, &bits_result);
-            
-            for (i, constraint) in constraints.iter().enumerate() {
-                assert_eq!(*constraint, M31::ZERO,
-                    "XOR failed for case ({:#x}, {:#x}), bit {}", a, b, i);
-            }
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "constraint {} failed", i);
         }
     }
 
     #[test]
-    fn test_bitwise_and_soundness() {
-        // Test that wrong result fails constraint
-        let a = 0xAAAAu32;
-        let b = 0x5555u32;
-        let wrong_result = 0xFFFFu32; // Should be 0x0000
-
-        let bits_a = u32_to_bits(a);
-        let bits_b = u32_to_bits(b);
-        let bits_wrong = u32_to_bits(wrong_result);
+    fn test_slt_constraints_mixed_sign() {
+        // 5 < -3 is false (5 is positive, -3 is negative)
+        let rs1 = 5u32;
+        let rs2 = (-3i32) as u32;
+        let (rs1_lo, rs1_hi) = u32_to_limbs(rs1);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(rs2);
+        let sign1 = M31::ZERO;
+        let sign2 = M31::ONE;
+
+        let sign_flip = 0x8000u32;
+        let flipped_rs1 = rs1 ^ (sign_flip << 16);
+        let flipped_rs2 = rs2 ^ (sign_flip << 16);
+        let (diff_lo, diff_hi, borrow_lo, borrow_hi) = borrow_witness(flipped_rs1, flipped_rs2);
+
+        let constraints = CpuAir::slt_constraints(
+            M31::ZERO, rs1_lo, rs1_hi, rs2_lo, rs2_hi, sign1, sign2, diff_lo, diff_hi, borrow_lo,
+            borrow_hi,
+        );
 
-        let constraints = CpuAir::bitwise_and_constraints(&bits_a, &bits_b, &bits_wrong);
-        
-        // Should have non-zero constraints
-        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
-        assert!(has_nonzero, "Constraint should catch incorrect AND result");
+        for constraint in constraints {
+            assert_eq!(constraint, M31::ZERO);
+        }
     }
 
     #[test]
-    fn test_bit_decomposition_soundness() {
-        // Test that incorrect bit decomposition fails
-        let value = 0x12345678u32;
-        let (lo, hi) = u32_to_limbs(value);
-        let mut bits = u32_to_bits(value);
-        
-        // Flip a bit
-        bits[5] = if bits[5] == M31::ZERO { M31::ONE } else { M31::ZERO };
+    fn test_slt_soundness_wrong_result() {
+        let rs1 = (-3i32) as u32;
+        let rs2 = (-2i32) as u32;
+        let (rs1_lo, rs1_hi) = u32_to_limbs(rs1);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(rs2);
+        let sign_flip = 0x8000u32;
+        let flipped_rs1 = rs1 ^ (sign_flip << 16);
+        let flipped_rs2 = rs2 ^ (sign_flip << 16);
+        let (diff_lo, diff_hi, borrow_lo, borrow_hi) = borrow_witness(flipped_rs1, flipped_rs2);
+
+        // -3 < -2 is true, so claiming 0 should be rejected.
+        let constraints = CpuAir::slt_constraints(
+            M31::ZERO, rs1_lo, rs1_hi, rs2_lo, rs2_hi, M31::ONE, M31::ONE, diff_lo, diff_hi,
+            borrow_lo, borrow_hi,
+        );
 
-        let constraints = CpuAir::bit_decomposition_constraints(lo, hi, &bits);
-        
-        // Should have non-zero constraints (reconstruction will fail)
         let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
-        assert!(has_nonzero, "Constraint should catch incorrect bit decomposition");
+        assert!(has_nonzero, "Constraint should catch an incorrect SLT result");
     }
 
     #[test]
-    fn test_shift_left_logical() {
-        // Test SLL: 0b1010 << 1 = 0b10100
-        let value = 0b1010u32;
-        let shift = 1u32;
-        let expected = value << shift;
-
-        let bits_value = u32_to_bits(value);
-        let bits_result = u32_to_bits(expected);
-        let shift_m31 = M31::new(shift);
-
-        let constraints = CpuAir::shift_left_logical_constraints(
-            &bits_value,
-            &bits_result,
-            shift_m31,
+    fn test_branch_pc_constraint_taken_and_not_taken() {
+        let pc = M31::new(100);
+        let imm = M31::new(16);
+
+        // Taken: next_pc = pc + imm
+        let taken_next_pc = pc + imm;
+        assert_eq!(
+            CpuAir::branch_pc_constraint(M31::ONE, pc, taken_next_pc, imm, M31::ONE),
+            M31::ZERO
         );
 
-        assert_eq!(constraints.len(), 32);
-        for (i, constraint) in constraints.iter().enumerate() {
-            assert_eq!(*constraint, M31::ZERO, "SLL constraint {} failed", i);
-        }
+        // Not taken: next_pc = pc + 4
+        let fallthrough_next_pc = pc + M31::new(4);
+        assert_eq!(
+            CpuAir::branch_pc_constraint(M31::ONE, pc, fallthrough_next_pc, imm, M31::ZERO),
+            M31::ZERO
+        );
     }
 
     #[test]
-    fn test_shift_left_comprehensive() {
-        let test_cases = [
-            (0x00000001, 0, 0x00000001),  // No shift
-            (0x00000001, 1, 0x00000002),  // Simple shift
-            (0x00000001, 31, 0x80000000), // Shift to MSB
-            (0xFFFFFFFF, 1, 0xFFFFFFFE),  // All ones
-            (0x12345678, 4, 0x23456780),  // Nibble shift
-            (0x00000001, 32, 0x00000001), // Shift by 32 (wraps to 0)
-        ];
+    fn test_branch_pc_constraint_rejects_wrong_target() {
+        let pc = M31::new(100);
+        let imm = M31::new(16);
+        let wrong_next_pc = pc + M31::new(8); // neither pc+imm nor pc+4
 
-        for (value, shift, expected) in test_cases {
-            let bits_value = u32_to_bits(value);
-            let bits_result = u32_to_bits(expected);
-            let shift_m31 = M31::new(shift);
+        let constraint = CpuAir::branch_pc_constraint(M31::ONE, pc, wrong_next_pc, imm, M31::ONE);
+        assert_ne!(constraint, M31::ZERO);
+    }
 
-            let constraints = CpuAir::shift_left_logical_constraints(
-                &bits_value,
-                &bits_result,
-                shift_m31,
-            );
+    #[test]
+    fn test_branch_taken_constraint_each_funct3() {
+        let one = M31::ONE;
+        let zero = M31::ZERO;
 
-            for (i, constraint) in constraints.iter().enumerate() {
-                assert_eq!(
-                    *constraint, M31::ZERO,
-                    "SLL({:#x} << {}) failed at bit {}", value, shift, i
-                );
-            }
-        }
+        // BEQ, equal
+        assert_eq!(
+            CpuAir::branch_taken_constraint(one, zero, zero, zero, zero, zero, one, zero, zero, one),
+            M31::ZERO
+        );
+        // BNE, not equal
+        assert_eq!(
+            CpuAir::branch_taken_constraint(zero, one, zero, zero, zero, zero, zero, zero, zero, one),
+            M31::ZERO
+        );
+        // BLT, taken
+        assert_eq!(
+            CpuAir::branch_taken_constraint(zero, zero, one, zero, zero, zero, zero, one, zero, one),
+            M31::ZERO
+        );
+        // BGE, taken (lt_result = 0)
+        assert_eq!(
+            CpuAir::branch_taken_constraint(zero, zero, zero, one, zero, zero, zero, zero, zero, one),
+            M31::ZERO
+        );
+        // BLTU, taken
+        assert_eq!(
+            CpuAir::branch_taken_constraint(zero, zero, zero, zero, one, zero, zero, zero, one, one),
+            M31::ZERO
+        );
+        // BGEU, taken (ltu_result = 0)
+        assert_eq!(
+            CpuAir::branch_taken_constraint(zero, zero, zero, zero, zero, one, zero, zero, zero, one),
+            M31::ZERO
+        );
     }
 
     #[test]
-    fn test_shift_right_logical() {
-        // Test SRL: 0b1010 >> 1 = 0b0101
-        let value = 0b1010u32;
-        let shift = 1u32;
-        let expected = value >> shift;
-
-        let bits_value = u32_to_bits(value);
-        let bits_result = u32_to_bits(expected);
-        let shift_m31 = M31::new(shift);
+    fn test_branch_taken_constraint_rejects_mismatch() {
+        // BEQ but claiming taken=1 while eq_result=0
+        let constraint = CpuAir::branch_taken_constraint(
+            M31::ONE,
+            M31::ZERO,
+            M31::ZERO,
+            M31::ZERO,
+            M31::ZERO,
+            M31::ZERO,
+            M31::ZERO, // eq_result
+            M31::ZERO,
+            M31::ZERO,
+            M31::ONE, // taken
+        );
+        assert_ne!(constraint, M31::ZERO);
+    }
 
-        let constraints = CpuAir::shift_right_logical_constraints(
-            &bits_value,
-            &bits_result,
-            shift_m31,
+    #[test]
+    fn test_branch_constraints_full_beq_taken() {
+        let pc = M31::new(200);
+        let imm = M31::new(32);
+        let next_pc = pc + imm;
+
+        let constraints = CpuAir::branch_constraints(
+            M31::ONE,
+            M31::ONE,
+            M31::ZERO,
+            M31::ZERO,
+            M31::ZERO,
+            M31::ZERO,
+            M31::ZERO,
+            M31::ONE, // eq_result
+            M31::ZERO,
+            M31::ZERO,
+            pc,
+            next_pc,
+            imm,
+            M31::ONE, // taken
         );
 
-        assert_eq!(constraints.len(), 32);
+        assert_eq!(constraints.len(), 2);
         for constraint in constraints {
             assert_eq!(constraint, M31::ZERO);
         }
     }
 
     #[test]
-    fn test_shift_right_logical_comprehensive() {
-        let test_cases = [
-            (0x80000000, 0, 0x80000000),  // No shift
-            (0x80000000, 1, 0x40000000),  // Shift MSB
-            (0x80000000, 31, 0x00000001), // Shift to LSB
-            (0xFFFFFFFF, 1, 0x7FFFFFFF),  // Zero-extend from left
-            (0x12345678, 4, 0x01234567),  // Nibble shift
-            (0x80000000, 32, 0x80000000), // Shift by 32 (wraps to 0)
-        ];
-
-        for (value, shift, expected) in test_cases {
-            let bits_value = u32_to_bits(value);
-            let bits_result = u32_to_bits(expected);
-            let shift_m31 = M31::new(shift);
+    fn test_logup_lookup_step_ext_valid() {
+        let x = QM31::new(M31::new(100), M31::new(1), M31::ZERO, M31::ZERO);
+        let value = M31::new(42);
+        let inv = (x - QM31::from(value)).inverse();
+        let acc = QM31::new(M31::new(7), M31::ZERO, M31::ZERO, M31::ZERO);
+        let acc_next = acc + inv;
 
-            let constraints = CpuAir::shift_right_logical_constraints(
-                &bits_value,
-                &bits_result,
-                shift_m31,
-            );
+        let constraints = CpuAir::logup_lookup_step_ext(x, value, inv, acc, acc_next);
 
-            for (i, constraint) in constraints.iter().enumerate() {
-                assert_eq!(
-                    *constraint, M31::ZERO,
-                    "SRL({:#x} >> {}) failed at bit {}", value, shift, i
-                );
-            }
+        assert_eq!(constraints.len(), 8);
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "constraint {} failed", i);
         }
     }
 
     #[test]
-    fn test_shift_right_arithmetic() {
-        // Test SRA with positive number (MSB = 0)
-        let value = 0b01010u32;
-        let shift = 1u32;
-        let expected = value >> shift; // 0b00101
+    fn test_logup_lookup_step_ext_rejects_wrong_inverse() {
+        let x = QM31::new(M31::new(100), M31::new(1), M31::ZERO, M31::ZERO);
+        let value = M31::new(42);
+        let wrong_inv = QM31::from(M31::ONE); // not 1/(x - value)
+        let acc = QM31::new(M31::new(7), M31::ZERO, M31::ZERO, M31::ZERO);
+        let acc_next = acc + wrong_inv;
 
-        let bits_value = u32_to_bits(value);
-        let bits_result = u32_to_bits(expected);
-        let shift_m31 = M31::new(shift);
+        let constraints = CpuAir::logup_lookup_step_ext(x, value, wrong_inv, acc, acc_next);
 
-        let constraints = CpuAir::shift_right_arithmetic_constraints(
-            &bits_value,
-            &bits_result,
-            shift_m31,
-        );
+        let has_nonzero = constraints[0..4].iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "bogus inverse witness should be caught");
+    }
 
-        assert_eq!(constraints.len(), 32);
-        for constraint in constraints {
-            assert_eq!(constraint, M31::ZERO);
+    #[test]
+    fn test_logup_lookup_step_ext_rejects_accumulator_mismatch() {
+        let x = QM31::new(M31::new(100), M31::new(1), M31::ZERO, M31::ZERO);
+        let value = M31::new(42);
+        let inv = (x - QM31::from(value)).inverse();
+        let acc = QM31::new(M31::new(7), M31::ZERO, M31::ZERO, M31::ZERO);
+        let wrong_acc_next = acc + inv + QM31::from(M31::ONE); // didn't fold inv in correctly
+
+        let constraints = CpuAir::logup_lookup_step_ext(x, value, inv, acc, wrong_acc_next);
+
+        let has_nonzero = constraints[4..8].iter().any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "accumulator update mismatch should be caught");
+    }
+
+    #[test]
+    fn test_logup_table_step_ext_valid() {
+        let x = QM31::new(M31::new(100), M31::new(1), M31::ZERO, M31::ZERO);
+        let table_entry = M31::new(42);
+        let multiplicity = M31::new(3);
+        let inv = (x - QM31::from(table_entry)).inverse();
+        let acc = QM31::new(M31::new(7), M31::ZERO, M31::ZERO, M31::ZERO);
+        let acc_next = acc + QM31::from(multiplicity) * inv;
+
+        let constraints =
+            CpuAir::logup_table_step_ext(x, table_entry, multiplicity, inv, acc, acc_next);
+
+        assert_eq!(constraints.len(), 8);
+        for (i, constraint) in constraints.iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "constraint {} failed", i);
         }
     }
 
     #[test]
-    fn test_shift_right_arithmetic_negative() {
-        // Test SRA with negative number (MSB = 1) - sign extension
-        let value = 0x80000000u32; // Negative in two's complement
-        let shift = 1u32;
-        let expected = 0xC0000000u32; // Sign-extended: 1100...
+    fn test_logup_close_constraint_ext() {
+        let total = QM31::new(M31::new(12345), M31::new(6), M31::ZERO, M31::new(9));
+        let other = QM31::new(M31::new(12345), M31::new(6), M31::ZERO, M31::new(10));
 
-        let bits_value = u32_to_bits(value);
-        let bits_result = u32_to_bits(expected);
-        let shift_m31 = M31::new(shift);
+        for constraint in CpuAir::logup_close_constraint_ext(total, total) {
+            assert_eq!(constraint, M31::ZERO);
+        }
 
-        let constraints = CpuAir::shift_right_arithmetic_constraints(
-            &bits_value,
-            &bits_result,
-            shift_m31,
-        );
+        let has_nonzero = CpuAir::logup_close_constraint_ext(total, other)
+            .iter()
+            .any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "mismatched QM31 accumulators should be caught");
+    }
 
-        for constraint in constraints {
-            assert_eq!(constraint, M31::ZERO, "SRA sign extension failed");
+    fn zero_row(layout: &StepLayout) -> Vec<M31> {
+        vec![M31::ZERO; layout.num_columns]
+    }
+
+    #[test]
+    fn test_step_layout_assigns_unique_columns() {
+        let layout = StepLayout::new();
+        let mut seen = std::collections::HashSet::new();
+        for &s in &layout.opcode_selectors() {
+            assert!(seen.insert(s), "duplicate column index {s}");
         }
+        assert!(layout.opcode_selectors().iter().all(|&i| i < layout.num_columns));
     }
 
     #[test]
-    fn test_shift_right_arithmetic_comprehensive() {
-        let test_cases = [
-            // (value, shift, expected_sra)
-            (0x00000008, 1, 0x00000004),  // Positive: 8 >> 1 = 4
-            (0x00000008, 2, 0x00000002),  // Positive: 8 >> 2 = 2
-            (0xFFFFFFF8u32, 1, 0xFFFFFFFCu32), // Negative: -8 >> 1 = -4 (sign extend)
-            (0xFFFFFFF8u32, 2, 0xFFFFFFFEu32), // Negative: -8 >> 2 = -2 (sign extend)
-            (0x80000000u32, 31, 0xFFFFFFFFu32), // Min int >> 31 = -1 (all ones)
-            (0x7FFFFFFF, 31, 0x00000000),  // Max int >> 31 = 0
-        ];
+    fn test_step_trace_builder_transposes_rows() {
+        let layout = StepLayout::new();
+        let mut builder = StepTraceBuilder::new(layout);
+
+        let mut row0 = zero_row(&layout);
+        row0[layout.pc] = M31::new(100);
+        let mut row1 = zero_row(&layout);
+        row1[layout.pc] = M31::new(104);
+
+        builder.push_row(row0);
+        builder.push_row(row1);
+        assert_eq!(builder.num_rows(), 2);
+
+        let columns = builder.finish();
+        assert_eq!(columns.len(), layout.num_columns);
+        assert_eq!(columns[layout.pc], vec![M31::new(100), M31::new(104)]);
+    }
 
-        for (value, shift, expected) in test_cases {
-            let bits_value = u32_to_bits(value);
-            let bits_result = u32_to_bits(expected);
-            let shift_m31 = M31::new(shift);
+    #[test]
+    #[should_panic(expected = "step row width does not match")]
+    fn test_step_trace_builder_rejects_wrong_width() {
+        let layout = StepLayout::new();
+        let mut builder = StepTraceBuilder::new(layout);
+        builder.push_row(vec![M31::ZERO; layout.num_columns - 1]);
+    }
 
-            let constraints = CpuAir::shift_right_arithmetic_constraints(
-                &bits_value,
-                &bits_result,
-                shift_m31,
-            );
+    /// Build a minimal valid ADD row: rd = rs1 + rs2, every other opcode
+    /// selector and unused witness left at 0.
+    fn add_row(layout: &StepLayout, rs1: u32, rs2: u32) -> Vec<M31> {
+        let mut row = zero_row(layout);
+        row[layout.is_add] = M31::ONE;
+
+        let (rs1_lo, rs1_hi) = u32_to_limbs(rs1);
+        let (rs2_lo, rs2_hi) = u32_to_limbs(rs2);
+        let (rd_lo, rd_hi) = u32_to_limbs(rs1.wrapping_add(rs2));
+        let carry = if (rs1 & 0xFFFF) + (rs2 & 0xFFFF) >= (1 << 16) {
+            M31::ONE
+        } else {
+            M31::ZERO
+        };
+
+        row[layout.rs1_val_lo] = rs1_lo;
+        row[layout.rs1_val_hi] = rs1_hi;
+        row[layout.rs2_val_lo] = rs2_lo;
+        row[layout.rs2_val_hi] = rs2_hi;
+        row[layout.rd_val_lo] = rd_lo;
+        row[layout.rd_val_hi] = rd_hi;
+        row[layout.add_carry] = carry;
+
+        row[layout.pc] = M31::new(100);
+        row[layout.next_pc] = M31::new(104);
+
+        row
+    }
 
-            for (i, constraint) in constraints.iter().enumerate() {
-                assert_eq!(
-                    *constraint, M31::ZERO,
-                    "SRA({:#x} >> {}) failed at bit {}, expected {:#x}",
-                    value, shift, i, expected
-                );
-            }
+    #[test]
+    fn test_step_row_constraints_valid_add_instruction() {
+        let layout = StepLayout::new();
+        let row = add_row(&layout, 7, 35);
+
+        for (i, constraint) in step_row_constraints(&layout, &row).iter().enumerate() {
+            assert_eq!(*constraint, M31::ZERO, "constraint {i} failed for valid ADD row");
         }
     }
 
     #[test]
-    fn test_shift_soundness() {
-        // Test that wrong shift result fails constraint
-        let value = 0x12345678u32;
-        let shift = 4u32;
-        let wrong_result = 0x23456781u32; // Should be 0x23456780
+    fn test_step_row_constraints_rejects_wrong_add_result() {
+        let layout = StepLayout::new();
+        let mut row = add_row(&layout, 7, 35);
+        row[layout.rd_val_lo] = row[layout.rd_val_lo] + M31::ONE;
+
+        let has_nonzero = step_row_constraints(&layout, &row)
+            .iter()
+            .any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "tampered ADD result should violate a constraint");
+    }
 
-        let bits_value = u32_to_bits(value);
-        let bits_wrong = u32_to_bits(wrong_result);
-        let shift_m31 = M31::new(shift);
+    #[test]
+    fn test_step_row_constraints_rejects_two_active_opcodes() {
+        let layout = StepLayout::new();
+        let mut row = add_row(&layout, 7, 35);
+        row[layout.is_mul] = M31::ONE;
+
+        let has_nonzero = step_row_constraints(&layout, &row)
+            .iter()
+            .any(|c| *c != M31::ZERO);
+        assert!(has_nonzero, "two active opcode selectors should violate the one-hot sum");
+    }
 
-        let constraints = CpuAir::shift_left_logical_constraints(
-            &bits_value,
-            &bits_wrong,
-            shift_m31,
-        );
+    #[test]
+    fn test_step_transition_constraints_pc_and_accumulators() {
+        let layout = StepLayout::new();
+        let mut row = add_row(&layout, 7, 35);
+        let mut next_row = add_row(&layout, 1, 2);
+        next_row[layout.pc] = row[layout.next_pc];
+
+        let mem_challenge = QM31::new(M31::new(11), M31::new(2), M31::ZERO, M31::ZERO);
+        let instr_challenge = QM31::new(M31::new(29), M31::ZERO, M31::new(3), M31::ZERO);
+
+        // is_load = is_store = 0, so the memory accumulator is gated off:
+        // any inv/acc witnesses (left at 0) are valid as long as acc
+        // doesn't advance.
+        row[layout.mem_perm_acc[0]] = M31::new(5);
+        next_row[layout.mem_perm_acc[0]] = M31::new(5);
+
+        // The instruction-lookup accumulator applies unconditionally, so
+        // it needs a real inverse witness for this row's instr_record.
+        let instr_record = M31::new(42);
+        row[layout.instr_record] = instr_record;
+        let inv = (instr_challenge - QM31::from(instr_record)).inverse();
+        row[layout.instr_perm_inv[0]] = inv.c0;
+        row[layout.instr_perm_inv[1]] = inv.c1;
+        row[layout.instr_perm_inv[2]] = inv.c2;
+        row[layout.instr_perm_inv[3]] = inv.c3;
+        let acc_next = QM31::new(
+            row[layout.instr_perm_acc[0]],
+            row[layout.instr_perm_acc[1]],
+            row[layout.instr_perm_acc[2]],
+            row[layout.instr_perm_acc[3]],
+        ) + inv;
+        next_row[layout.instr_perm_acc[0]] = acc_next.c0;
+        next_row[layout.instr_perm_acc[1]] = acc_next.c1;
+        next_row[layout.instr_perm_acc[2]] = acc_next.c2;
+        next_row[layout.instr_perm_acc[3]] = acc_next.c3;
+
+        for (i, constraint) in
+            step_transition_constraints(&layout, &row, &next_row, mem_challenge, instr_challenge)
+                .iter()
+                .enumerate()
+        {
+            assert_eq!(*constraint, M31::ZERO, "transition constraint {i} failed");
+        }
+    }
 
-        let has_nonzero = constraints.iter().any(|c| *c != M31::ZERO);
-        assert!(has_nonzero, "Constraint should catch incorrect shift result");
+    #[test]
+    fn test_step_transition_constraints_rejects_broken_pc_chain() {
+        let layout = StepLayout::new();
+        let row = add_row(&layout, 7, 35);
+        let mut next_row = add_row(&layout, 1, 2);
+        next_row[layout.pc] = row[layout.next_pc] + M31::ONE;
+
+        let mem_challenge = QM31::new(M31::new(11), M31::new(2), M31::ZERO, M31::ZERO);
+        let instr_challenge = QM31::new(M31::new(29), M31::ZERO, M31::new(3), M31::ZERO);
+
+        assert_ne!(
+            step_transition_constraints(&layout, &row, &next_row, mem_challenge, instr_challenge)[0],
+            M31::ZERO
+        );
     }
 }
@@ -0,0 +1,328 @@
+//! EIP-198 `MODEXP` precompile gadget: fixed-width modular exponentiation
+//! over big-endian byte strings, delegated from the zkVM guest via
+//! `zp1_zkvm::syscalls::modexp` so the RISC-V interpreter never has to
+//! execute the exponentiation loop itself.
+
+use thiserror::Error;
+
+/// Upper bound on `base`/`exponent`/`modulus` byte length this gadget
+/// will allocate for. EIP-198 itself places no hard cap on operand
+/// length (gas cost alone bounds it on mainnet), but a delegated gadget
+/// must reject a malicious length header before it ever reaches
+/// `Vec::with_capacity`. 128 bytes (1024 bits) comfortably covers
+/// RSA-1024-class moduli.
+pub const MAX_OPERAND_LEN: usize = 128;
+
+/// Errors returned by [`modexp`].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ModexpError {
+    #[error("input too short to hold the three 32-byte length headers")]
+    TruncatedHeader,
+
+    #[error("operand length {len} exceeds MAX_OPERAND_LEN ({MAX_OPERAND_LEN})")]
+    OperandTooLarge { len: usize },
+
+    #[error("input too short to hold base/exponent/modulus of the declared lengths")]
+    TruncatedOperands,
+}
+
+/// Evaluate the `MODEXP` precompile (EIP-198) over raw calldata: three
+/// big-endian 32-byte length headers (`base_len`, `exp_len`, `mod_len`)
+/// followed by `base_len + exp_len + mod_len` bytes of operand data.
+///
+/// Returns exactly `mod_len` bytes, matching the precompile's output
+/// size rule even when the modulus is zero-length or itself zero.
+pub fn modexp(input: &[u8]) -> Result<Vec<u8>, ModexpError> {
+    if input.len() < 96 {
+        return Err(ModexpError::TruncatedHeader);
+    }
+
+    let base_len = read_len(&input[0..32])?;
+    let exp_len = read_len(&input[32..64])?;
+    let mod_len = read_len(&input[64..96])?;
+
+    // EIP-198: a zero-length modulus always yields empty output,
+    // regardless of base/exponent.
+    if mod_len == 0 {
+        return Ok(Vec::new());
+    }
+
+    let operands = &input[96..];
+    if operands.len() < base_len + exp_len + mod_len {
+        return Err(ModexpError::TruncatedOperands);
+    }
+
+    let base = &operands[0..base_len];
+    let exponent = &operands[base_len..base_len + exp_len];
+    let modulus = &operands[base_len + exp_len..base_len + exp_len + mod_len];
+
+    Ok(mod_pow(base, exponent, modulus))
+}
+
+/// Read a 32-byte big-endian length header, rejecting anything that
+/// would exceed `MAX_OPERAND_LEN` before any allocation happens.
+fn read_len(header: &[u8]) -> Result<usize, ModexpError> {
+    // A legitimate length never needs more than the low-order 8 bytes; a
+    // header with any higher byte set already implies a length far past
+    // MAX_OPERAND_LEN, so reject it without ever forming that usize.
+    if header[..24].iter().any(|&b| b != 0) {
+        return Err(ModexpError::OperandTooLarge { len: MAX_OPERAND_LEN + 1 });
+    }
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&header[24..32]);
+    let len = u64::from_be_bytes(low) as usize;
+    if len > MAX_OPERAND_LEN {
+        return Err(ModexpError::OperandTooLarge { len });
+    }
+    Ok(len)
+}
+
+/// Compute `base^exponent mod modulus`, all given as big-endian byte
+/// strings, via fixed-width `u32` limbs: every multiply accumulates in a
+/// `u64` (so a limb product can never silently wrap) and is followed by
+/// a reduction back down to the modulus's width before the next
+/// multiply, the same invariant long division maintains throughout.
+fn mod_pow(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+    let mod_len = modulus.len();
+
+    // EIP-198: modulus == 0 yields an all-zero output of modulus length,
+    // no matter what the base/exponent are.
+    if modulus.iter().all(|&b| b == 0) {
+        return vec![0u8; mod_len];
+    }
+
+    let modulus_limbs = limbs_from_be_bytes(modulus);
+    let width = modulus_limbs.len();
+
+    // `1 mod modulus`, not literal `1`: if modulus == 1 the correct
+    // starting value (and exponent == 0 result) is 0, not 1.
+    let mut result = reduce(&one_limbs(width), &modulus_limbs);
+    let base_mod = reduce(&limbs_from_be_bytes(base), &modulus_limbs);
+
+    // Left-to-right square-and-multiply over the exponent's bits,
+    // MSB first. An empty or all-zero exponent never hits the
+    // multiply-by-base branch, so `result` stays `1 mod modulus`.
+    for byte in exponent {
+        for bit in (0..8).rev() {
+            result = reduce(&mul_full(&result, &result), &modulus_limbs);
+            if (byte >> bit) & 1 == 1 {
+                result = reduce(&mul_full(&result, &base_mod), &modulus_limbs);
+            }
+        }
+    }
+
+    limbs_to_be_bytes(&result, mod_len)
+}
+
+/// Parse a big-endian byte string into little-endian `u32` limbs (limb 0
+/// is the least-significant word), sized to exactly fit `bytes`.
+fn limbs_from_be_bytes(bytes: &[u8]) -> Vec<u32> {
+    if bytes.is_empty() {
+        return vec![0u32];
+    }
+    let width = bytes.len().div_ceil(4);
+    let mut limbs = vec![0u32; width];
+    for (i, chunk) in bytes.rchunks(4).enumerate() {
+        let mut word = [0u8; 4];
+        word[4 - chunk.len()..].copy_from_slice(chunk);
+        limbs[i] = u32::from_be_bytes(word);
+    }
+    limbs
+}
+
+/// Render `width` little-endian `u32` limbs back to a big-endian byte
+/// string of exactly `byte_len` bytes (truncating leading zero limbs or
+/// zero-padding, whichever `byte_len` calls for).
+fn limbs_to_be_bytes(limbs: &[u32], byte_len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; limbs.len() * 4];
+    let total = bytes.len();
+    for (i, limb) in limbs.iter().enumerate() {
+        bytes[total - 4 * (i + 1)..total - 4 * i].copy_from_slice(&limb.to_be_bytes());
+    }
+
+    if bytes.len() >= byte_len {
+        bytes[bytes.len() - byte_len..].to_vec()
+    } else {
+        let mut padded = vec![0u8; byte_len - bytes.len()];
+        padded.extend_from_slice(&bytes);
+        padded
+    }
+}
+
+/// The limb representation of `1`.
+fn one_limbs(width: usize) -> Vec<u32> {
+    let mut limbs = vec![0u32; width.max(1)];
+    limbs[0] = 1;
+    limbs
+}
+
+/// Full (unreduced) product of two limb vectors: `a.len() + b.len()`
+/// limbs. Every limb-pair product accumulates in a `u64`, which can
+/// never overflow (`u32::MAX * u32::MAX` plus two more `u32` carries
+/// still fits comfortably under `u64::MAX`), so this never wraps.
+fn mul_full(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, &ai) in a.iter().enumerate() {
+        let mut carry: u64 = 0;
+        for (j, &bj) in b.iter().enumerate() {
+            let idx = i + j;
+            let prod = ai as u64 * bj as u64 + result[idx] as u64 + carry;
+            result[idx] = prod as u32;
+            carry = prod >> 32;
+        }
+        let mut k = i + b.len();
+        while carry > 0 {
+            let sum = result[k] as u64 + carry;
+            result[k] = sum as u32;
+            carry = sum >> 32;
+            k += 1;
+        }
+    }
+    result
+}
+
+/// Reduce `value` (any width) modulo `modulus`, returning exactly
+/// `modulus.len()` limbs, via bit-at-a-time binary long division:
+/// shift the running remainder left by one bit, bring in the next bit
+/// of `value`, and subtract `modulus` out whenever the remainder has
+/// grown to meet or exceed it.
+fn reduce(value: &[u32], modulus: &[u32]) -> Vec<u32> {
+    let width = modulus.len();
+    let mut remainder = vec![0u32; width];
+
+    for &limb in value.iter().rev() {
+        for bit in (0..32).rev() {
+            let overflowed = shift_left_one(&mut remainder);
+            if (limb >> bit) & 1 == 1 {
+                remainder[0] |= 1;
+            }
+            if overflowed || ge(&remainder, modulus) {
+                sub_assign(&mut remainder, modulus);
+            }
+        }
+    }
+
+    remainder
+}
+
+/// Shift `limbs` left by one bit in place; returns whether a bit was
+/// lost off the top of the most-significant limb.
+fn shift_left_one(limbs: &mut [u32]) -> bool {
+    let mut carry = 0u32;
+    for limb in limbs.iter_mut() {
+        let next_carry = *limb >> 31;
+        *limb = (*limb << 1) | carry;
+        carry = next_carry;
+    }
+    carry == 1
+}
+
+/// `a >= b` for two same-width little-endian limb vectors.
+fn ge(a: &[u32], b: &[u32]) -> bool {
+    for i in (0..a.len()).rev() {
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
+
+/// `a -= b` for two same-width little-endian limb vectors. Any borrow
+/// past the most-significant limb is discarded: callers only subtract
+/// when `a` (including an already-accounted-for overflow bit) is known
+/// to be within one `modulus` of the desired range, so the wrapped
+/// result is exactly the value they want.
+fn sub_assign(a: &mut [u32], b: &[u32]) {
+    let mut borrow: i64 = 0;
+    for i in 0..a.len() {
+        let diff = a[i] as i64 - b[i] as i64 - borrow;
+        if diff < 0 {
+            a[i] = (diff + (1i64 << 32)) as u32;
+            borrow = 1;
+        } else {
+            a[i] = diff as u32;
+            borrow = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_input(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+        let mut input = Vec::new();
+        for len in [base.len(), exponent.len(), modulus.len()] {
+            input.extend_from_slice(&[0u8; 24]);
+            input.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+        input.extend_from_slice(base);
+        input.extend_from_slice(exponent);
+        input.extend_from_slice(modulus);
+        input
+    }
+
+    #[test]
+    fn test_known_vector_two_pow_ten_mod_thousand() {
+        // 2^10 mod 1000 = 24. Modulus 1000 big-endian over 2 bytes: 0x03E8.
+        let output = modexp(&build_input(&[2], &[10], &[0x03, 0xe8])).unwrap();
+        assert_eq!(output, vec![0x00, 0x18]); // 24
+    }
+
+    #[test]
+    fn test_zero_length_modulus_returns_empty_output() {
+        let input = build_input(&[7], &[3], &[]);
+        assert_eq!(modexp(&input).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_modulus_zero_returns_all_zero_output() {
+        let input = build_input(&[7], &[3], &[0, 0, 0, 0]);
+        assert_eq!(modexp(&input).unwrap(), vec![0u8; 4]);
+    }
+
+    #[test]
+    fn test_exponent_zero_returns_one_mod_modulus() {
+        let input = build_input(&[0xff, 0xee], &[], &[7]);
+        assert_eq!(modexp(&input).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn test_exponent_zero_with_modulus_one_returns_zero() {
+        let input = build_input(&[5], &[0, 0, 0], &[1]);
+        assert_eq!(modexp(&input).unwrap(), vec![0]);
+    }
+
+    #[test]
+    fn test_maximal_length_operands_does_not_overflow() {
+        let base = vec![0xabu8; MAX_OPERAND_LEN];
+        let exponent = vec![0xcdu8; MAX_OPERAND_LEN];
+        let mut modulus = vec![0xefu8; MAX_OPERAND_LEN];
+        // Force the modulus odd so `modulus == 0` can't short-circuit
+        // the exponentiation loop this test is meant to exercise.
+        *modulus.last_mut().unwrap() |= 1;
+
+        let input = build_input(&base, &exponent, &modulus);
+        let output = modexp(&input).unwrap();
+        assert_eq!(output.len(), MAX_OPERAND_LEN);
+    }
+
+    #[test]
+    fn test_oversized_length_header_is_rejected_before_allocation() {
+        let mut input = vec![0u8; 96];
+        let oversized = (MAX_OPERAND_LEN + 1) as u64;
+        input[24..32].copy_from_slice(&oversized.to_be_bytes());
+        assert_eq!(modexp(&input), Err(ModexpError::OperandTooLarge { len: oversized as usize }));
+    }
+
+    #[test]
+    fn test_truncated_header_is_rejected() {
+        assert_eq!(modexp(&[0u8; 95]), Err(ModexpError::TruncatedHeader));
+    }
+
+    #[test]
+    fn test_truncated_operands_is_rejected() {
+        let input = build_input(&[1, 2, 3], &[4], &[5, 6]);
+        assert_eq!(modexp(&input[..input.len() - 1]), Err(ModexpError::TruncatedOperands));
+    }
+}
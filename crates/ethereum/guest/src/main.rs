@@ -12,13 +12,15 @@ extern crate alloc;
 use alloc::vec::Vec;
 use revm::{
     db::{CacheDB, EmptyDB},
+    interpreter::Interpreter,
     primitives::{
-        AccountInfo, Address as RevmAddress, Bytes, CreateScheme, ExecutionResult, Output,
-        TransactTo, U256 as RevmU256,
+        AccountInfo, Address as RevmAddress, Bytecode, Bytes, CreateScheme, ExecutionResult,
+        Output, TransactTo, U256 as RevmU256,
     },
-    EVM,
+    Database, EVMData, Inspector, EVM,
 };
 use serde::{Deserialize, Serialize};
+use zp1_zkvm::prelude::keccak256;
 
 /// Input data for the guest program
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,14 +32,121 @@ pub struct TxInput {
     pub gas_price: Option<[u8; 32]>,
     pub input: Vec<u8>,
     pub nonce: u64,
+    /// Whether to gather a `TraceStep` per executed opcode. Off by
+    /// default since the inspector hook adds overhead the proof still
+    /// has to pay for even when nobody wants `debug_traceTransaction`
+    /// semantics out of it.
+    pub trace: bool,
 }
 
-/// Output data from the guest program
+/// Chain/block context the host supplies for this transaction's
+/// environment (`evm.env.cfg`/`evm.env.block`). Without this the guest
+/// executes every transaction against revm's all-zero default
+/// environment, which a guest program or precompile that reads
+/// `BLOCKHASH`/`TIMESTAMP`/`BASEFEE`/`COINBASE` would silently get wrong.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnvInput {
+    pub chain_id: u64,
+    pub block_number: u64,
+    pub timestamp: u64,
+    pub base_fee: [u8; 32],
+    pub coinbase: [u8; 20],
+}
+
+/// Pre-state the host supplies for every account (and storage slot) this
+/// transaction is expected to touch, including the sender. Without this
+/// the guest can only execute against an empty, unfunded database.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateWitness {
+    pub accounts: Vec<AccountWitness>,
+}
+
+/// One account's pre-state: balance, nonce, and code, plus the pre-values
+/// of any storage slots the transaction touches.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountWitness {
+    pub address: [u8; 20],
+    pub balance: [u8; 32],
+    pub nonce: u64,
+    pub code: Vec<u8>,
+    pub storage: Vec<([u8; 32], [u8; 32])>,
+}
+
+/// An event log emitted during execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Log {
+    pub address: [u8; 20],
+    pub topics: Vec<[u8; 32]>,
+    pub data: Vec<u8>,
+}
+
+/// One recorded execution step, mirroring the fields Geth's
+/// `debug_traceTransaction` reports per opcode. Lighter than the host
+/// prover's `TraceStep` (`zp1_ethereum::evm::TraceStep`), which also
+/// captures the full stack and memory size to feed the AIR — the guest
+/// only needs enough to reproduce `debug_traceTransaction`-shaped output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub pc: usize,
+    pub opcode: u8,
+    pub gas_remaining: u64,
+    pub stack_depth: usize,
+}
+
+/// A REVM [`Inspector`] that records a [`TraceStep`] per executed opcode.
+#[derive(Debug, Default)]
+pub struct GuestTracer {
+    pub steps: Vec<TraceStep>,
+}
+
+impl<DB: Database> Inspector<DB> for GuestTracer {
+    fn step(&mut self, interp: &mut Interpreter, _data: &mut EVMData<'_, DB>) {
+        self.steps.push(TraceStep {
+            pc: interp.program_counter(),
+            opcode: interp.current_opcode(),
+            gas_remaining: interp.gas().remaining(),
+            stack_depth: interp.stack().data().len(),
+        });
+    }
+}
+
+/// Outcome of a transaction's execution attempt.
+///
+/// `InvalidSender` is distinct from `Reverted`: it means the transaction
+/// never reached the EVM at all, rejected per EIP-3607 because `from` is
+/// a contract account (non-empty code) rather than an EOA — real nodes
+/// refuse to even charge gas for such a transaction, let alone execute
+/// it, so the proof must not report it as a revert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxStatus {
+    Success,
+    Reverted,
+    Halted,
+    InvalidSender,
+}
+
+/// Output data from the guest program: a receipt-shaped result binding
+/// the proof to the logs and (changed) state the transaction produced.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TxOutput {
-    pub success: bool,
+    pub status: TxStatus,
     pub gas_used: u64,
     pub return_data: Vec<u8>,
+    /// Keccak256 chain over every storage slot this transaction changed
+    /// (sorted by address then slot, so the commitment is independent of
+    /// revm's internal iteration order), binding the proof to a concrete
+    /// post-state without shipping the full state diff as a public output.
+    pub state_commitment: [u8; 32],
+    /// Address of the contract created by a `Create` transaction.
+    pub created_address: Option<[u8; 20]>,
+    /// Logs emitted during execution, in emission order.
+    pub logs: Vec<Log>,
+    /// Keccak256 chain over `logs`, so callers can verify event emission
+    /// was part of the proven execution without the full log data being
+    /// a public output.
+    pub logs_commitment: [u8; 32],
+    /// Per-opcode trace, present only when `TxInput::trace` was set.
+    pub trace: Option<Vec<TraceStep>>,
 }
 
 // Entry point for the zkVM guest
@@ -57,35 +166,123 @@ pub extern "C" fn main() {
 fn execute_transaction_guest() {
     // This would read from zkVM IO in real implementation
     // For demonstration, showing the logic that will execute in the guest
-    
+
     // Placeholder: In reality, read from zkVM stdin
-    // let tx_input: TxInput = read_from_zkvm_io();
-    
+    // let tx_input: TxInput = read();
+    // let state_witness: StateWitness = read();
+    // let env_input: EnvInput = read();
+
     // Execute the transaction
-    // let result = execute_tx_internal(&tx_input);
-    
+    // let result = execute_tx_internal(&tx_input, &state_witness, &env_input);
+
     // Commit result to journal
-    // write_to_zkvm_io(&result);
+    // commit(&result);
 }
 
-/// Execute a transaction using revm (runs inside the zkVM)
-fn execute_tx_internal(tx_input: &TxInput) -> TxOutput {
-    // Initialize EVM with empty DB (in production, would have pre-state)
+/// Populate a `CacheDB` from the host-supplied pre-state, so the guest can
+/// execute against real account balances, nonces, code, and storage
+/// instead of an empty database.
+fn build_db(state: &StateWitness) -> CacheDB<EmptyDB> {
     let mut db = CacheDB::new(EmptyDB::default());
-    
-    // Setup sender account
+
+    for account in &state.accounts {
+        let address = RevmAddress::from_slice(&account.address);
+        let code = if account.code.is_empty() {
+            None
+        } else {
+            Some(Bytecode::new_raw(Bytes::from(account.code.clone())))
+        };
+        let code_hash = match &code {
+            Some(bytecode) => RevmU256::from_be_bytes(keccak256(bytecode.bytes())).into(),
+            None => RevmU256::ZERO.into(),
+        };
+
+        db.insert_account_info(
+            address,
+            AccountInfo { balance: RevmU256::from_be_bytes(account.balance), nonce: account.nonce, code_hash, code },
+        );
+
+        for (slot, value) in &account.storage {
+            db.insert_account_storage(address, RevmU256::from_be_bytes(*slot), RevmU256::from_be_bytes(*value))
+                .unwrap();
+        }
+    }
+
+    db
+}
+
+/// Keccak256-chain a list of changed storage slots, sorted by address then
+/// slot, into a single 32-byte commitment: starting from an all-zero
+/// digest `h_{-1} = 0^32`, `h_i = keccak(h_{i-1} || address || slot ||
+/// value)`. An empty list commits to the all-zero digest.
+fn commit_changed_slots(changed: &[([u8; 20], [u8; 32], [u8; 32])]) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    for (address, slot, value) in changed {
+        let mut buf = [0u8; 32 + 20 + 32 + 32];
+        buf[0..32].copy_from_slice(&digest);
+        buf[32..52].copy_from_slice(address);
+        buf[52..84].copy_from_slice(slot);
+        buf[84..116].copy_from_slice(value);
+        digest = keccak256(&buf);
+    }
+    digest
+}
+
+/// Keccak256-chain the logs a transaction emitted, in emission order,
+/// into a single 32-byte commitment, the same way `commit_changed_slots`
+/// folds storage writes.
+fn commit_logs(logs: &[Log]) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    for log in logs {
+        let mut buf = Vec::with_capacity(32 + 20 + log.topics.len() * 32 + log.data.len());
+        buf.extend_from_slice(&digest);
+        buf.extend_from_slice(&log.address);
+        for topic in &log.topics {
+            buf.extend_from_slice(topic);
+        }
+        buf.extend_from_slice(&log.data);
+        digest = keccak256(&buf);
+    }
+    digest
+}
+
+/// EIP-3607: a transaction whose sender is an account with deployed code
+/// (rather than an EOA) must be rejected outright, before it ever reaches
+/// the EVM — code can't originate a transaction, real or proven.
+fn sender_is_contract(state: &StateWitness, sender: &[u8; 20]) -> bool {
+    state.accounts.iter().any(|account| account.address == *sender && !account.code.is_empty())
+}
+
+/// Execute a transaction using revm (runs inside the zkVM)
+fn execute_tx_internal(tx_input: &TxInput, state: &StateWitness, env: &EnvInput) -> TxOutput {
+    if sender_is_contract(state, &tx_input.from) {
+        return TxOutput {
+            status: TxStatus::InvalidSender,
+            gas_used: 0,
+            return_data: Vec::new(),
+            state_commitment: [0u8; 32],
+            created_address: None,
+            logs: Vec::new(),
+            logs_commitment: [0u8; 32],
+            trace: None,
+        };
+    }
+
+    let db = build_db(state);
     let sender = RevmAddress::from_slice(&tx_input.from);
-    let sender_info = AccountInfo {
-        balance: RevmU256::from(10_000_000_000_000_000_000u128), // 10 ETH
-        nonce: tx_input.nonce,
-        code_hash: RevmU256::ZERO.into(),
-        code: None,
-    };
-    db.insert_account_info(sender, sender_info);
 
     let mut evm = EVM::new();
     evm.database(db);
 
+    // Configure the chain/block environment the host observed, so
+    // opcodes like TIMESTAMP/BASEFEE/COINBASE see real values instead of
+    // revm's all-zero defaults.
+    evm.env.cfg.chain_id = env.chain_id;
+    evm.env.block.number = RevmU256::from(env.block_number);
+    evm.env.block.timestamp = RevmU256::from(env.timestamp);
+    evm.env.block.basefee = RevmU256::from_be_bytes(env.base_fee);
+    evm.env.block.coinbase = RevmAddress::from_slice(&env.coinbase);
+
     // Configure transaction
     evm.env.tx.caller = sender;
     evm.env.tx.transact_to = if let Some(to) = tx_input.to {
@@ -100,30 +297,71 @@ fn execute_tx_internal(tx_input: &TxInput) -> TxOutput {
         evm.env.tx.gas_price = RevmU256::from_be_bytes(price);
     }
 
-    // Execute
-    let result = evm.transact_commit().unwrap();
+    // Execute without committing, so we can read back the state diff
+    // (`transact_commit` discards it) to build the journal commitment.
+    // Only attach the tracer when the host actually asked for a trace —
+    // it costs a push per opcode the proof still has to account for.
+    let mut tracer = GuestTracer::default();
+    let output = if tx_input.trace {
+        evm.inspect(&mut tracer).unwrap()
+    } else {
+        evm.transact().unwrap()
+    };
+    let result = output.result;
+
+    let mut changed = Vec::new();
+    for (address, account) in &output.state {
+        for (slot, value) in &account.storage {
+            if value.present_value != value.original_value {
+                changed.push((
+                    address.as_slice().try_into().unwrap(),
+                    slot.to_be_bytes::<32>(),
+                    value.present_value.to_be_bytes::<32>(),
+                ));
+            }
+        }
+    }
+    changed.sort();
+    let state_commitment = commit_changed_slots(&changed);
 
     // Process result
-    let (success, return_data, gas_used) = match result {
-        ExecutionResult::Success { output, gas_used, .. } => {
+    let mut created_address = None;
+    let (status, return_data, gas_used, raw_logs) = match result {
+        ExecutionResult::Success { output, gas_used, logs, .. } => {
             let data = match output {
                 Output::Call(bytes) => bytes.to_vec(),
-                Output::Create(bytes, _) => bytes.to_vec(),
+                Output::Create(bytes, address) => {
+                    created_address = address.map(|a| a.as_slice().try_into().unwrap());
+                    bytes.to_vec()
+                }
             };
-            (true, data, gas_used)
+            (TxStatus::Success, data, gas_used, logs)
         }
         ExecutionResult::Revert { output, gas_used } => {
-            (false, output.to_vec(), gas_used)
-        }
-        ExecutionResult::Halt { gas_used, .. } => {
-            (false, Vec::new(), gas_used)
+            (TxStatus::Reverted, output.to_vec(), gas_used, Vec::new())
         }
+        ExecutionResult::Halt { gas_used, .. } => (TxStatus::Halted, Vec::new(), gas_used, Vec::new()),
     };
 
+    let logs: Vec<Log> = raw_logs
+        .into_iter()
+        .map(|log| Log {
+            address: log.address.as_slice().try_into().unwrap(),
+            topics: log.topics.iter().map(|topic| topic.as_slice().try_into().unwrap()).collect(),
+            data: log.data.to_vec(),
+        })
+        .collect();
+    let logs_commitment = commit_logs(&logs);
+
     TxOutput {
-        success,
+        status,
         gas_used,
         return_data,
+        state_commitment,
+        created_address,
+        logs,
+        logs_commitment,
+        trace: tx_input.trace.then_some(tracer.steps),
     }
 }
 
@@ -1,15 +1,98 @@
 use revm::{
     db::{CacheDB, EmptyDB},
+    interpreter::Interpreter,
     primitives::{
         AccountInfo, Address as RevmAddress, Bytes, CreateScheme, ExecutionResult, Output,
         TransactTo, U256 as RevmU256,
     },
-    EVM,
+    Database, EVMData, Inspector, EVM,
 };
+use ethers::abi::{encode, Token};
 use ethers::types::{Address as EthersAddress, H256 as EthersH256, U256 as EthersU256};
+use ethers::utils::keccak256;
+use zp1_primitives::{to_limbs, M31};
 use crate::fetcher::TransactionData;
 use crate::transaction::TransactionResult;
 
+/// `SLOAD` opcode, whose sole stack input (the slot) becomes a storage
+/// read once it resolves to a value.
+const OP_SLOAD: u8 = 0x54;
+/// `SSTORE` opcode, whose two stack inputs (slot, value) are a storage
+/// write outright — the write takes effect before the next step runs.
+const OP_SSTORE: u8 = 0x55;
+
+/// One recorded execution step: the opcode at `pc`, what it saw on the
+/// stack and in memory, and any storage it touched. A `Vec<TraceStep>` is
+/// exactly the fetch-decode-execute trace `trace_to_columns` lowers into
+/// prover-ready columns.
+#[derive(Debug, Clone)]
+pub struct TraceStep {
+    /// Program counter of the executed instruction.
+    pub pc: usize,
+    /// Opcode byte at `pc`.
+    pub opcode: u8,
+    /// Gas remaining before this instruction ran.
+    pub gas_remaining: u64,
+    /// Full stack contents before this instruction ran.
+    pub stack: Vec<RevmU256>,
+    /// Memory size in bytes before this instruction ran.
+    pub memory_size: usize,
+    /// `(slot, value)` for an `SLOAD`/`SSTORE` this step performed.
+    pub storage_access: Option<(RevmU256, RevmU256)>,
+}
+
+/// A REVM [`Inspector`] that records a [`TraceStep`] per executed
+/// opcode, making the fetch-decode-execute loop REVM actually ran
+/// available to `trace_to_columns` instead of discarding it the way
+/// `transact_commit` does.
+#[derive(Debug, Default)]
+pub struct TracingInspector {
+    /// Steps recorded so far, in execution order.
+    pub steps: Vec<TraceStep>,
+}
+
+impl<DB: Database> Inspector<DB> for TracingInspector {
+    fn step(&mut self, interp: &mut Interpreter, _data: &mut EVMData<'_, DB>) {
+        let opcode = interp.current_opcode();
+        let stack = interp.stack().data();
+
+        // SSTORE's slot/value are both consumed by this step, so the
+        // write is known before it runs. SLOAD's result isn't known
+        // until `step_end`.
+        let storage_access = if opcode == OP_SSTORE && stack.len() >= 2 {
+            let slot = stack[stack.len() - 1];
+            let value = stack[stack.len() - 2];
+            Some((slot, value))
+        } else {
+            None
+        };
+
+        self.steps.push(TraceStep {
+            pc: interp.program_counter(),
+            opcode,
+            gas_remaining: interp.gas().remaining(),
+            stack: stack.clone(),
+            memory_size: interp.memory.len(),
+            storage_access,
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _data: &mut EVMData<'_, DB>) {
+        let Some(last) = self.steps.last_mut() else {
+            return;
+        };
+        if last.opcode != OP_SLOAD || last.storage_access.is_some() {
+            return;
+        }
+        // The slot SLOAD read is the one value left on its own stack
+        // frame before it ran; the post-execution stack now carries the
+        // value it resolved to in that same slot.
+        if let (Some(&slot), Some(&value)) = (last.stack.last(), interp.stack().data().last()) {
+            last.storage_access = Some((slot, value));
+        }
+    }
+}
+
 /// Convert Ethers Address to Revm Address
 fn to_revm_address(addr: EthersAddress) -> RevmAddress {
     RevmAddress::from_slice(addr.as_bytes())
@@ -22,12 +105,13 @@ fn to_revm_u256(val: EthersU256) -> RevmU256 {
     RevmU256::from_be_bytes(bytes)
 }
 
-/// Execute a transaction using Revm
-pub fn execute_tx(tx: &TransactionData) -> anyhow::Result<TransactionResult> {
+/// Build a fresh in-memory EVM with `tx`'s sender funded and its
+/// transaction fields configured, ready for `transact`/`inspect`.
+fn build_evm(tx: &TransactionData) -> EVM<CacheDB<EmptyDB>> {
     // Initialize EVM with empty DB
     // In a real scenario, we would load state from a provider or disk
     let mut db = CacheDB::new(EmptyDB::default());
-    
+
     // Setup sender account with some balance so they can pay for gas
     let sender = to_revm_address(tx.from);
     let sender_info = AccountInfo {
@@ -55,12 +139,13 @@ pub fn execute_tx(tx: &TransactionData) -> anyhow::Result<TransactionResult> {
         evm.env.tx.gas_price = to_revm_u256(price);
     }
 
-    // Execute
-    let result_and_state = evm.transact_commit()?;
-    let result = result_and_state;
+    evm
+}
 
-    // Process result
-    let (success, return_data, gas_used) = match result {
+/// Turn a raw REVM [`ExecutionResult`] into the `(success, return_data,
+/// gas_used)` triple `execute_tx`/`execute_tx_with_trace` both return.
+fn unpack_result(result: ExecutionResult) -> (bool, Vec<u8>, u64) {
+    match result {
         ExecutionResult::Success { output, gas_used, .. } => {
             let data = match output {
                 Output::Call(bytes) => bytes.to_vec(),
@@ -68,27 +153,190 @@ pub fn execute_tx(tx: &TransactionData) -> anyhow::Result<TransactionResult> {
             };
             (true, data, gas_used)
         }
-        ExecutionResult::Revert { output, gas_used } => {
-            (false, output.to_vec(), gas_used)
-        }
-        ExecutionResult::Halt { reason: _, gas_used } => {
-            (false, vec![], gas_used)
-        }
-    };
+        ExecutionResult::Revert { output, gas_used } => (false, output.to_vec(), gas_used),
+        ExecutionResult::Halt { reason: _, gas_used } => (false, vec![], gas_used),
+    }
+}
 
-    // Collect state changes (simplified)
-    // In a real implementation, we would inspect the State returned by transact()
-    // But transact_commit() consumes it. 
-    // For now, we'll return an empty list of state changes as we are using EmptyDB
+/// Execute a transaction using Revm
+pub fn execute_tx(tx: &TransactionData) -> anyhow::Result<TransactionResult> {
+    let (result, _trace) = execute_tx_with_trace(tx)?;
+    Ok(result)
+}
+
+/// Execute a transaction using Revm, returning the step-by-step
+/// [`TraceStep`] trace a [`TracingInspector`] recorded alongside the
+/// result.
+///
+/// Uses `evm.inspect`, not `evm.transact_commit`, so the `State` REVM
+/// produces stays inspectable instead of being committed and discarded —
+/// the inspector attached to the same call captures the opcode trace
+/// [`trace_to_columns`] needs to feed `StarkProver`.
+pub fn execute_tx_with_trace(
+    tx: &TransactionData,
+) -> anyhow::Result<(TransactionResult, Vec<TraceStep>)> {
+    let mut evm = build_evm(tx);
+    let mut inspector = TracingInspector::default();
+
+    let result_and_state = evm.inspect(&mut inspector)?;
+    let (success, return_data, gas_used) = unpack_result(result_and_state.result);
+
+    // Collect state changes (simplified): with `inspect` (unlike
+    // `transact_commit`) the resulting `State` is available here rather
+    // than already consumed, but we're still on `EmptyDB` with nothing
+    // upstream to diff against, so there's nothing meaningful to report
+    // yet beyond what `TraceStep::storage_access` already captures.
     let state_changes = Vec::new();
 
-    Ok(TransactionResult {
+    let result = TransactionResult {
         hash: tx.hash,
         gas_used,
         success,
         return_data,
         state_changes,
-    })
+    };
+
+    Ok((result, inspector.steps))
+}
+
+/// Lower a REVM execution trace into the column-major `Vec<Vec<M31>>`
+/// layout `StarkProver` consumes: one row per [`TraceStep`], one column
+/// per field. 256-bit EVM words (the top-of-stack value and any
+/// storage slot/value touched) are decomposed into 16-bit limbs via
+/// [`to_limbs`] — eight 32-bit words per 256-bit word, two limbs per
+/// word — the same scheme `memory`/`ram` access records use, so these
+/// columns slot directly into that subsystem's layout.
+pub fn trace_to_columns(trace: &[TraceStep]) -> Vec<Vec<M31>> {
+    const WORDS_PER_256: usize = 8;
+    const LIMB_COLUMNS: usize = WORDS_PER_256 * 2;
+
+    let mut pc = Vec::with_capacity(trace.len());
+    let mut opcode = Vec::with_capacity(trace.len());
+    let mut gas_remaining = Vec::with_capacity(trace.len());
+    let mut memory_size = Vec::with_capacity(trace.len());
+    let mut stack_top: Vec<Vec<M31>> = vec![Vec::with_capacity(trace.len()); LIMB_COLUMNS];
+    let mut storage_slot: Vec<Vec<M31>> = vec![Vec::with_capacity(trace.len()); LIMB_COLUMNS];
+    let mut storage_value: Vec<Vec<M31>> = vec![Vec::with_capacity(trace.len()); LIMB_COLUMNS];
+
+    for step in trace {
+        pc.push(M31::new(step.pc as u32));
+        opcode.push(M31::new(step.opcode as u32));
+        // Gas can exceed the M31 modulus; only its low bits matter for
+        // trace replay (the AIR constrains gas as a running difference,
+        // not an absolute value), so truncating is sound here.
+        gas_remaining.push(M31::new(step.gas_remaining as u32));
+        memory_size.push(M31::new(step.memory_size as u32));
+
+        push_word_limbs(&mut stack_top, step.stack.last().copied().unwrap_or(RevmU256::ZERO));
+        let (slot, value) = step.storage_access.unwrap_or((RevmU256::ZERO, RevmU256::ZERO));
+        push_word_limbs(&mut storage_slot, slot);
+        push_word_limbs(&mut storage_value, value);
+    }
+
+    let mut columns = vec![pc, opcode, gas_remaining, memory_size];
+    columns.extend(stack_top);
+    columns.extend(storage_slot);
+    columns.extend(storage_value);
+    columns
+}
+
+/// Decompose a 256-bit big-endian word into its eight 32-bit words, each
+/// split into a `(low, high)` 16-bit limb pair via [`to_limbs`], and push
+/// every limb (lifted to `M31`) onto its column in `limb_columns`.
+fn push_word_limbs(limb_columns: &mut [Vec<M31>], word: RevmU256) {
+    let bytes = word.to_be_bytes::<32>();
+    for (word_idx, chunk) in bytes.chunks_exact(4).enumerate() {
+        let word32 = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        let (low, high) = to_limbs(word32);
+        limb_columns[word_idx * 2].push(M31::new(low as u32));
+        limb_columns[word_idx * 2 + 1].push(M31::new(high as u32));
+    }
+}
+
+/// Compile a ZP1 SNARK verifier contract and deploy it into a fresh,
+/// empty-state REVM instance, then call its `verify(bytes,uint256[])`
+/// entry point against `proof_bytes`/`public_inputs`. Used by this
+/// module's own round-trip test, and available to callers that want to
+/// sanity-check a [`zp1_prover::generate_verifier_contract`] output
+/// against a real EVM before paying gas to deploy it anywhere that
+/// matters.
+pub fn deploy_and_call_verifier(
+    vk: &zp1_prover::serialize::VerificationKey,
+    system: zp1_prover::SnarkSystem,
+    proof_bytes: Vec<u8>,
+    public_inputs: Vec<[u8; 32]>,
+) -> anyhow::Result<ExecutionResult> {
+    let source = zp1_prover::generate_verifier_contract(vk, system);
+    let contract_name = zp1_prover::verifier_contract_name(system);
+
+    let tmp_dir = std::env::temp_dir().join(format!("zp1-verifier-{}", std::process::id()));
+    std::fs::create_dir_all(&tmp_dir)?;
+    let contract_path = tmp_dir.join("Verifier.sol");
+    std::fs::write(&contract_path, &source)?;
+
+    let compiled = ethers_solc::Solc::default().compile_source(&contract_path)?;
+    let artifact = compiled
+        .get(
+            contract_path
+                .to_str()
+                .ok_or_else(|| anyhow::anyhow!("non-UTF-8 temp path"))?,
+            contract_name,
+        )
+        .ok_or_else(|| anyhow::anyhow!("solc did not produce the {contract_name} artifact"))?;
+    let bytecode = artifact
+        .bytecode
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("{contract_name} compiled with no bytecode"))?
+        .object
+        .as_bytes()
+        .ok_or_else(|| anyhow::anyhow!("{contract_name} bytecode has unresolved library links"))?
+        .clone();
+
+    // Deploy, the same way `execute_tx` creates a contract.
+    let deployer = RevmAddress::from([0x11u8; 20]);
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(
+        deployer,
+        AccountInfo {
+            balance: RevmU256::from(10_000_000_000_000_000_000u128),
+            nonce: 0,
+            code_hash: RevmU256::ZERO.into(),
+            code: None,
+        },
+    );
+
+    let mut evm = EVM::new();
+    evm.database(db);
+    evm.env.tx.caller = deployer;
+    evm.env.tx.transact_to = TransactTo::Create(CreateScheme::Create);
+    evm.env.tx.data = Bytes::from(bytecode.to_vec());
+    evm.env.tx.value = RevmU256::ZERO;
+    evm.env.tx.gas_limit = 5_000_000;
+
+    let deploy_result = evm.transact_commit()?;
+    let contract_address = match deploy_result {
+        ExecutionResult::Success { output: Output::Create(_, Some(address)), .. } => address,
+        other => anyhow::bail!("verifier contract deployment failed: {other:?}"),
+    };
+
+    // Call `verify(bytes,uint256[])`.
+    let selector = &keccak256(b"verify(bytes,uint256[])")[0..4];
+    let encoded_args = encode(&[
+        Token::Bytes(proof_bytes),
+        Token::Array(
+            public_inputs
+                .iter()
+                .map(|word| Token::Uint(EthersU256::from_big_endian(word)))
+                .collect(),
+        ),
+    ]);
+    let mut calldata = selector.to_vec();
+    calldata.extend(encoded_args);
+
+    evm.env.tx.transact_to = TransactTo::Call(contract_address);
+    evm.env.tx.data = Bytes::from(calldata);
+
+    Ok(evm.transact_commit()?)
 }
 
 #[cfg(test)]
@@ -114,8 +362,138 @@ mod tests {
         };
 
         let result = execute_tx(&tx).expect("Execution failed");
-        
+
         assert!(result.success);
         assert_eq!(result.gas_used, 21000);
     }
+
+    fn transfer_tx() -> TransactionData {
+        TransactionData {
+            hash: EthersH256::random(),
+            from: EthersAddress::random(),
+            to: Some(EthersAddress::random()),
+            value: U256::from(1000),
+            gas: 21000,
+            gas_price: Some(U256::from(10)),
+            input: vec![],
+            nonce: 0,
+        }
+    }
+
+    #[test]
+    fn test_execute_tx_with_trace_records_steps() {
+        let tx = transfer_tx();
+
+        let (result, trace) = execute_tx_with_trace(&tx).expect("Execution failed");
+
+        assert!(result.success);
+        // A plain value transfer still runs through the interpreter (at
+        // minimum the implicit STOP at an empty code account).
+        assert!(!trace.is_empty());
+    }
+
+    #[test]
+    fn test_execute_tx_matches_traced_execution() {
+        let tx = transfer_tx();
+
+        let plain = execute_tx(&tx).expect("execute_tx failed");
+        let (traced, _) = execute_tx_with_trace(&tx).expect("execute_tx_with_trace failed");
+
+        assert_eq!(plain.success, traced.success);
+        assert_eq!(plain.gas_used, traced.gas_used);
+    }
+
+    #[test]
+    fn test_trace_to_columns_shapes_rows_by_step_count() {
+        let tx = transfer_tx();
+        let (_, trace) = execute_tx_with_trace(&tx).expect("Execution failed");
+
+        let columns = trace_to_columns(&trace);
+
+        assert!(!columns.is_empty());
+        for column in &columns {
+            assert_eq!(column.len(), trace.len());
+        }
+    }
+
+    #[test]
+    fn test_push_word_limbs_round_trips_through_to_limbs() {
+        let word = RevmU256::from(0x1234_5678_9abc_def0u64);
+        let mut columns = vec![Vec::new(); 16];
+
+        push_word_limbs(&mut columns, word);
+
+        for column in &columns {
+            assert_eq!(column.len(), 1);
+        }
+        // The low 32-bit word's limbs must round-trip through `to_limbs`.
+        let bytes = word.to_be_bytes::<32>();
+        let last_word32 = u32::from_be_bytes([bytes[28], bytes[29], bytes[30], bytes[31]]);
+        let (low, high) = to_limbs(last_word32);
+        assert_eq!(columns[14][0], M31::new(low as u32));
+        assert_eq!(columns[15][0], M31::new(high as u32));
+    }
+
+    fn test_vk() -> zp1_prover::serialize::VerificationKey {
+        zp1_prover::serialize::VerificationKey {
+            config: zp1_prover::serialize::ProofConfig {
+                log_trace_len: 10,
+                blowup_factor: 8,
+                num_queries: 30,
+                fri_folding_factor: 2,
+                security_bits: 100,
+                entry_point: 0,
+            },
+            constraints_hash: [7u8; 32],
+            // `abi.encodePacked(uint256[])` is just the tight concatenation
+            // of each 32-byte element, matching the generated contract's
+            // `keccak256(abi.encodePacked(publicInputs))` check.
+            public_inputs_hash: {
+                let mut word = [0u8; 32];
+                EthersU256::from(42u64).to_big_endian(&mut word);
+                keccak256(word)
+            },
+        }
+    }
+
+    #[test]
+    fn test_generated_verifier_accepts_valid_proof_on_chain() {
+        let vk = test_vk();
+        let mut proof_bytes = vk.constraints_hash.to_vec();
+        proof_bytes.extend_from_slice(&[0u8; 32]); // placeholder SNARK-specific proof data
+
+        let mut public_input = [0u8; 32];
+        EthersU256::from(42u64).to_big_endian(&mut public_input);
+
+        let result = deploy_and_call_verifier(
+            &vk,
+            zp1_prover::SnarkSystem::Groth16,
+            proof_bytes,
+            vec![public_input],
+        )
+        .expect("deployment and call failed");
+
+        assert!(matches!(result, ExecutionResult::Success { .. }));
+    }
+
+    #[test]
+    fn test_generated_verifier_reverts_on_tampered_public_input() {
+        let vk = test_vk();
+        let mut proof_bytes = vk.constraints_hash.to_vec();
+        proof_bytes.extend_from_slice(&[0u8; 32]);
+
+        // Tampered: does not hash to vk.public_inputs_hash.
+        let mut public_input = [0u8; 32];
+        EthersU256::from(43u64).to_big_endian(&mut public_input);
+
+        let result = deploy_and_call_verifier(
+            &vk,
+            zp1_prover::SnarkSystem::Groth16,
+            proof_bytes,
+            vec![public_input],
+        )
+        .expect("deployment and call failed");
+
+        assert!(matches!(result, ExecutionResult::Revert { .. }));
+    }
 }
@@ -4,10 +4,24 @@
 
 use crate::error::ExecutorError;
 use crate::memory::Memory;
+use std::collections::HashMap;
 
 /// ELF magic number.
 const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
 
+/// Magic bytes for the loader's own compressed-ELF container. Not a
+/// standard format — just enough of a header to tell a wrapped,
+/// possibly-compressed blob apart from a raw ELF before looking at the
+/// codec id.
+const COMPRESSED_MAGIC: [u8; 4] = [b'Z', b'P', b'1', b'C'];
+
+/// Size of the compressed-ELF container header: 4-byte magic + 4-byte
+/// little-endian uncompressed length + 1-byte codec id.
+const COMPRESSED_HEADER_SIZE: usize = 9;
+
+/// Codec id for the compressed-ELF container: raw DEFLATE (RFC 1951).
+const CODEC_DEFLATE: u8 = 0;
+
 /// ELF class: 32-bit.
 const ELFCLASS32: u8 = 1;
 
@@ -20,9 +34,137 @@ const EM_RISCV: u16 = 243;
 /// Program header type: loadable segment.
 const PT_LOAD: u32 = 1;
 
+/// Program header type: dynamic linking information.
+const PT_DYNAMIC: u32 = 2;
+
+/// ELF type: executable file (fixed load address).
+const ET_EXEC: u16 = 2;
+
+/// ELF type: shared object / position-independent executable.
+const ET_DYN: u16 = 3;
+
+/// Dynamic section tag: end of `DT_*` entries.
+const DT_NULL: i32 = 0;
+/// Dynamic section tag: address of the `.dynstr` string table.
+const DT_STRTAB: i32 = 5;
+/// Dynamic section tag: address of the `.dynsym` symbol table.
+const DT_SYMTAB: i32 = 6;
+/// Dynamic section tag: address of the `.rela.dyn` relocation table.
+const DT_RELA: i32 = 7;
+/// Dynamic section tag: total size in bytes of the `.rela.dyn` table.
+const DT_RELASZ: i32 = 8;
+/// Dynamic section tag: size in bytes of one `Elf32_Rela` entry.
+const DT_RELAENT: i32 = 9;
+/// Dynamic section tag: total size in bytes of the `.dynstr` table.
+const DT_STRSZ: i32 = 10;
+/// Dynamic section tag: size in bytes of one `.dynsym` entry.
+const DT_SYMENT: i32 = 11;
+
+/// Base virtual address a position-independent executable is loaded at.
+///
+/// Static-PIE RISC-V binaries link with `p_vaddr` starting at (or near) 0,
+/// leaving the actual load address up to the loader. We pick a fixed base
+/// rather than true ASLR since execution must stay deterministic for
+/// proving.
+const PIE_LOAD_BASE: u32 = 0x0040_0000;
+
+/// RISC-V relocation type: `S + A` (symbol value plus addend).
+const R_RISCV_32: u32 = 1;
+/// RISC-V relocation type: `B + A` (load-bias-relative, symbol-independent).
+const R_RISCV_RELATIVE: u32 = 3;
+/// RISC-V relocation type: `S` (resolved symbol value), used for PLT/GOT
+/// jump-slot entries.
+const R_RISCV_JUMP_SLOT: u32 = 5;
+/// RISC-V relocation type: `S` (resolved symbol value), used for non-PLT GOT
+/// entries.
+const R_RISCV_GLOB_DAT: u32 = 6;
+
+/// Section header type: symbol table.
+const SHT_SYMTAB: u32 = 2;
+
+/// Section header size in bytes for ELF32.
+const SHDR_SIZE: usize = 40;
+
+/// Symbol table entry size in bytes for ELF32.
+const SYM_SIZE: usize = 16;
+
+/// Guest page size, for the `AT_PAGESZ` auxiliary vector entry.
+const PAGE_SIZE: u32 = 4096;
+
+/// Auxiliary vector type: terminator.
+const AT_NULL: u32 = 0;
+/// Auxiliary vector type: address of the program header table.
+const AT_PHDR: u32 = 3;
+/// Auxiliary vector type: size of one program header entry.
+const AT_PHENT: u32 = 4;
+/// Auxiliary vector type: number of program header entries.
+const AT_PHNUM: u32 = 5;
+/// Auxiliary vector type: system page size.
+const AT_PAGESZ: u32 = 6;
+/// Auxiliary vector type: base address the interpreter/PIE was loaded at.
+const AT_BASE: u32 = 7;
+/// Auxiliary vector type: program entry point.
+const AT_ENTRY: u32 = 9;
+/// Auxiliary vector type: address of 16 random bytes.
+const AT_RANDOM: u32 = 25;
+
+/// ELF32 section header.
+#[derive(Debug, Clone)]
+struct Elf32SectionHeader {
+    /// Offset into the section header string table.
+    sh_name: u32,
+    /// Section type (`SHT_*`).
+    sh_type: u32,
+    /// File offset of the section's data.
+    sh_offset: u32,
+    /// Size of the section's data in bytes.
+    sh_size: u32,
+    /// Section header index of the associated string table (for symtabs,
+    /// this is the linked strtab).
+    sh_link: u32,
+    /// Size of each entry, for sections holding a fixed-size table.
+    sh_entsize: u32,
+}
+
+/// A resolved ELF symbol: its name and the address it points to (already
+/// biased for PIE binaries, matching [`ElfLoader::entry_point`]).
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    /// Symbol name.
+    pub name: String,
+    /// Resolved address.
+    pub address: u32,
+}
+
+/// A parsed `Elf32_Rela` relocation entry.
+#[derive(Debug, Clone, Copy)]
+pub struct Elf32Rela {
+    /// Virtual address (pre-bias) to patch.
+    pub r_offset: u32,
+    /// Relocation type and symbol index, packed as `(sym << 8) | type`.
+    pub r_info: u32,
+    /// Addend used when computing the relocated value.
+    pub r_addend: i32,
+}
+
+impl Elf32Rela {
+    /// The `ELF32_R_TYPE` component of `r_info`.
+    pub fn r_type(&self) -> u32 {
+        self.r_info & 0xff
+    }
+
+    /// The `ELF32_R_SYM` component of `r_info`: the index into `.dynsym`
+    /// this relocation resolves against.
+    pub fn r_sym(&self) -> u32 {
+        self.r_info >> 8
+    }
+}
+
 /// ELF file header (32-bit).
 #[derive(Debug, Clone)]
 pub struct Elf32Header {
+    /// Object file type (`ET_EXEC`, `ET_DYN`, ...).
+    pub e_type: u16,
     /// Entry point address.
     pub entry: u32,
     /// Program header table offset.
@@ -74,6 +216,110 @@ pub struct ElfLoader {
     header: Elf32Header,
     /// Parsed program headers.
     program_headers: Vec<Elf32ProgramHeader>,
+    /// Symbol table, name to resolved (bias-applied) address. Empty if the
+    /// binary has no `.symtab` (e.g. stripped).
+    symbols: HashMap<String, u32>,
+}
+
+/// Parse the `.symtab`/`.strtab` pair (the first `SHT_SYMTAB` section and
+/// the string table it links to) into a name-to-address map, biased for
+/// PIE binaries. Returns an empty map if there is no symbol table.
+fn parse_symbols(
+    data: &[u8],
+    section_headers: &[Elf32SectionHeader],
+    bias: u32,
+) -> Result<HashMap<String, u32>, ExecutorError> {
+    let Some(symtab) = section_headers.iter().find(|sh| sh.sh_type == SHT_SYMTAB) else {
+        return Ok(HashMap::new());
+    };
+
+    let strtab = section_headers
+        .get(symtab.sh_link as usize)
+        .ok_or_else(|| ExecutorError::InvalidElf("Symbol table sh_link out of bounds".into()))?;
+
+    let entsize = if symtab.sh_entsize == 0 { SYM_SIZE as u32 } else { symtab.sh_entsize } as usize;
+    let sym_offset = symtab.sh_offset as usize;
+    let sym_size = symtab.sh_size as usize;
+    if sym_offset + sym_size > data.len() {
+        return Err(ExecutorError::InvalidElf("Symbol table out of bounds".into()));
+    }
+
+    let str_offset = strtab.sh_offset as usize;
+    let str_size = strtab.sh_size as usize;
+    if str_offset + str_size > data.len() {
+        return Err(ExecutorError::InvalidElf("String table out of bounds".into()));
+    }
+    let strtab_data = &data[str_offset..str_offset + str_size];
+
+    let mut symbols = HashMap::new();
+    for entry_offset in (sym_offset..sym_offset + sym_size).step_by(entsize) {
+        if entry_offset + SYM_SIZE > data.len() {
+            return Err(ExecutorError::InvalidElf("Symbol entry out of bounds".into()));
+        }
+
+        let st_name = u32::from_le_bytes([
+            data[entry_offset],
+            data[entry_offset + 1],
+            data[entry_offset + 2],
+            data[entry_offset + 3],
+        ]);
+        let st_value = u32::from_le_bytes([
+            data[entry_offset + 4],
+            data[entry_offset + 5],
+            data[entry_offset + 6],
+            data[entry_offset + 7],
+        ]);
+
+        if st_name == 0 {
+            continue; // Unnamed (e.g. the null symbol or a section symbol).
+        }
+        let name = read_c_str(strtab_data, st_name as usize)?;
+        symbols.insert(name, st_value.wrapping_add(bias));
+    }
+
+    Ok(symbols)
+}
+
+/// Read a NUL-terminated string out of a string table starting at `offset`.
+fn read_c_str(strtab: &[u8], offset: usize) -> Result<String, ExecutorError> {
+    let bytes = strtab
+        .get(offset..)
+        .ok_or_else(|| ExecutorError::InvalidElf("String table offset out of bounds".into()))?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    Ok(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+/// Decompress `payload` per `codec` (one of the `CODEC_*` ids), refusing to
+/// produce more than `uncompressed_len` bytes.
+///
+/// The length cap guards against a decompression bomb: a container that
+/// advertises a small `uncompressed_len` but unpacks to something huge is
+/// cut off one byte past the advertised length, so the caller's length
+/// check below fails fast instead of first materializing the whole thing.
+#[cfg(feature = "compression")]
+fn decompress(codec: u8, payload: &[u8], uncompressed_len: usize) -> Result<Vec<u8>, ExecutorError> {
+    use std::io::Read;
+
+    match codec {
+        CODEC_DEFLATE => {
+            let mut out = Vec::with_capacity(uncompressed_len.min(1 << 20));
+            flate2::read::DeflateDecoder::new(payload)
+                .take(uncompressed_len as u64 + 1)
+                .read_to_end(&mut out)
+                .map_err(|e| ExecutorError::InvalidElf(format!("DEFLATE decompression failed: {e}")))?;
+            Ok(out)
+        }
+        other => Err(ExecutorError::InvalidElf(format!(
+            "Unsupported compressed ELF codec id: {other}"
+        ))),
+    }
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress(_codec: u8, _payload: &[u8], _uncompressed_len: usize) -> Result<Vec<u8>, ExecutorError> {
+    Err(ExecutorError::InvalidElf(
+        "Compressed ELF payloads require the `compression` feature".into(),
+    ))
 }
 
 impl ElfLoader {
@@ -106,8 +352,18 @@ impl ElfLoader {
             ));
         }
 
+        // Check object type (executable or PIE; we don't support relocatable
+        // or core files)
+        let e_type = u16::from_le_bytes([data[16], data[17]]);
+        if e_type != ET_EXEC && e_type != ET_DYN {
+            return Err(ExecutorError::InvalidElf(
+                format!("Unsupported ELF type: {}", e_type)
+            ));
+        }
+
         // Parse header
         let header = Elf32Header {
+            e_type,
             entry: u32::from_le_bytes([data[24], data[25], data[26], data[27]]),
             phoff: u32::from_le_bytes([data[28], data[29], data[30], data[31]]),
             shoff: u32::from_le_bytes([data[32], data[33], data[34], data[35]]),
@@ -125,6 +381,17 @@ impl ElfLoader {
         let phoff = header.phoff as usize;
         let phentsize = header.phentsize as usize;
 
+        if phentsize < 32 {
+            return Err(ExecutorError::InvalidElf(
+                format!("Program header entry size too small: {}", phentsize),
+            ));
+        }
+        if phoff + header.phnum as usize * phentsize > data.len() {
+            return Err(ExecutorError::InvalidElf(
+                "Program header table exceeds file bounds".into(),
+            ));
+        }
+
         for i in 0..header.phnum as usize {
             let offset = phoff + i * phentsize;
             if offset + 32 > data.len() {
@@ -145,16 +412,137 @@ impl ElfLoader {
             program_headers.push(ph);
         }
 
+        // Parse section headers, if present, to locate the symbol table.
+        let mut section_headers = Vec::new();
+        let shoff = header.shoff as usize;
+        let shentsize = header.shentsize as usize;
+
+        if header.shnum > 0 && shentsize > 0 {
+            for i in 0..header.shnum as usize {
+                let offset = shoff + i * shentsize;
+                if offset + SHDR_SIZE > data.len() {
+                    return Err(ExecutorError::InvalidElf("Section header out of bounds".into()));
+                }
+
+                section_headers.push(Elf32SectionHeader {
+                    sh_name: u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]),
+                    sh_type: u32::from_le_bytes([data[offset + 4], data[offset + 5], data[offset + 6], data[offset + 7]]),
+                    sh_offset: u32::from_le_bytes([data[offset + 16], data[offset + 17], data[offset + 18], data[offset + 19]]),
+                    sh_size: u32::from_le_bytes([data[offset + 20], data[offset + 21], data[offset + 22], data[offset + 23]]),
+                    sh_link: u32::from_le_bytes([data[offset + 24], data[offset + 25], data[offset + 26], data[offset + 27]]),
+                    sh_entsize: u32::from_le_bytes([data[offset + 36], data[offset + 37], data[offset + 38], data[offset + 39]]),
+                });
+            }
+        }
+
+        let bias = if e_type == ET_DYN { PIE_LOAD_BASE } else { 0 };
+
+        // A segment can't occupy less memory than it has file bytes to
+        // load; following crosvm's ImagePastRamEnd handling, that (and the
+        // biased vaddr ranges colliding with one another) is rejected here
+        // rather than left to fault unpredictably at load/execution time.
+        let mut load_ranges: Vec<(u32, u32, bool)> = Vec::new();
+        for ph in program_headers.iter().filter(|ph| ph.p_type == PT_LOAD) {
+            if ph.p_memsz < ph.p_filesz {
+                return Err(ExecutorError::SegmentExceedsMemory {
+                    p_filesz: ph.p_filesz,
+                    p_memsz: ph.p_memsz,
+                });
+            }
+
+            let start = ph.p_vaddr.wrapping_add(bias);
+            let end = start.wrapping_add(ph.p_memsz);
+            let executable = ph.p_flags & segment_flags::PF_X != 0;
+
+            for &(other_start, other_end, _) in &load_ranges {
+                if start < other_end && other_start < end {
+                    return Err(ExecutorError::OverlappingSegment {
+                        start,
+                        other_start,
+                    });
+                }
+            }
+            load_ranges.push((start, end, executable));
+        }
+
+        let entry = header.entry.wrapping_add(bias);
+        if !load_ranges
+            .iter()
+            .any(|&(start, end, executable)| executable && entry >= start && entry < end)
+        {
+            return Err(ExecutorError::EntrypointOutOfBounds { entry });
+        }
+
+        let symbols = parse_symbols(data, &section_headers, bias)?;
+
         Ok(Self {
             data: data.to_vec(),
             header,
             program_headers,
+            symbols,
         })
     }
 
-    /// Get the entry point address.
+    /// Parse an ELF that may be wrapped in the loader's own compressed
+    /// container, used to ship guest binaries compressed to cut
+    /// proving-input size (the same trick the Wii Homebrew Channel uses to
+    /// wrap ELFs in an LZMA payload).
+    ///
+    /// The container is `COMPRESSED_MAGIC` (4 bytes), the uncompressed
+    /// length (`u32`, little-endian), a codec id (1 byte), then the
+    /// compressed bytes. Plain ELF input (starting with the ELF magic)
+    /// parses unchanged via [`Self::parse`]. The decompressed length is
+    /// checked against the header's advertised length so a lying header
+    /// can't be used to smuggle a decompression bomb past the loader.
+    pub fn parse_maybe_compressed(data: &[u8]) -> Result<Self, ExecutorError> {
+        if !data.starts_with(&COMPRESSED_MAGIC) {
+            return Self::parse(data);
+        }
+        if data.len() < COMPRESSED_HEADER_SIZE {
+            return Err(ExecutorError::InvalidElf(
+                "Compressed ELF container header truncated".into(),
+            ));
+        }
+
+        let uncompressed_len =
+            u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+        let codec = data[8];
+        let payload = &data[COMPRESSED_HEADER_SIZE..];
+
+        let decompressed = decompress(codec, payload, uncompressed_len)?;
+        if decompressed.len() != uncompressed_len {
+            return Err(ExecutorError::InvalidElf(format!(
+                "Compressed ELF container advertised {} uncompressed bytes but decompression produced {} (possible decompression bomb)",
+                uncompressed_len,
+                decompressed.len()
+            )));
+        }
+
+        Self::parse(&decompressed)
+    }
+
+    /// True if this is a position-independent executable (`ET_DYN`) rather
+    /// than a fixed-address executable (`ET_EXEC`).
+    pub fn is_pie(&self) -> bool {
+        self.header.e_type == ET_DYN
+    }
+
+    /// The address offset applied to every `p_vaddr` / relocation target.
+    ///
+    /// Zero for `ET_EXEC` binaries, which already encode absolute
+    /// addresses. PIE binaries link at address 0 and are shifted up to
+    /// [`PIE_LOAD_BASE`] so they don't collide with the zero page.
+    pub fn load_bias(&self) -> u32 {
+        if self.is_pie() {
+            PIE_LOAD_BASE
+        } else {
+            0
+        }
+    }
+
+    /// Get the entry point address, biased for PIE binaries.
     pub fn entry_point(&self) -> u32 {
-        self.header.entry
+        self.header.entry.wrapping_add(self.load_bias())
     }
 
     /// Get loadable segments.
@@ -162,12 +550,299 @@ impl ElfLoader {
         self.program_headers.iter().filter(|ph| ph.p_type == PT_LOAD)
     }
 
-    /// Load the ELF into memory.
+    /// Translate a pre-bias virtual address to a file offset, if it falls
+    /// within a loadable segment's file-backed range.
+    fn vaddr_to_file_offset(&self, vaddr: u32) -> Option<usize> {
+        self.loadable_segments().find_map(|ph| {
+            let seg_end = ph.p_vaddr.checked_add(ph.p_filesz)?;
+            if vaddr >= ph.p_vaddr && vaddr < seg_end {
+                Some((ph.p_offset + (vaddr - ph.p_vaddr)) as usize)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Parse the `.rela.dyn` relocation table referenced by the
+    /// `PT_DYNAMIC` segment, if any.
+    ///
+    /// Only the dynamic tags needed to locate the RELA table are
+    /// understood (`DT_RELA`, `DT_RELASZ`, `DT_RELAENT`); other tags are
+    /// skipped. Binaries with no `PT_DYNAMIC` segment (plain static
+    /// `ET_EXEC`) have no relocations and return an empty vector.
+    pub fn relocations(&self) -> Result<Vec<Elf32Rela>, ExecutorError> {
+        let Some(dynamic) = self.program_headers.iter().find(|ph| ph.p_type == PT_DYNAMIC) else {
+            return Ok(Vec::new());
+        };
+
+        let mut rela_vaddr = None;
+        let mut rela_size = None;
+        let mut rela_entsize = 12usize;
+
+        let dyn_offset = dynamic.p_offset as usize;
+        let dyn_size = dynamic.p_filesz as usize;
+        if dyn_offset + dyn_size > self.data.len() {
+            return Err(ExecutorError::InvalidElf("PT_DYNAMIC out of bounds".into()));
+        }
+
+        for entry_offset in (dyn_offset..dyn_offset + dyn_size).step_by(8) {
+            if entry_offset + 8 > self.data.len() {
+                return Err(ExecutorError::InvalidElf("Dynamic entry out of bounds".into()));
+            }
+            let tag = i32::from_le_bytes([
+                self.data[entry_offset],
+                self.data[entry_offset + 1],
+                self.data[entry_offset + 2],
+                self.data[entry_offset + 3],
+            ]);
+            let val = u32::from_le_bytes([
+                self.data[entry_offset + 4],
+                self.data[entry_offset + 5],
+                self.data[entry_offset + 6],
+                self.data[entry_offset + 7],
+            ]);
+
+            if tag == DT_NULL {
+                break;
+            } else if tag == DT_RELA {
+                rela_vaddr = Some(val);
+            } else if tag == DT_RELASZ {
+                rela_size = Some(val as usize);
+            } else if tag == DT_RELAENT {
+                rela_entsize = val as usize;
+            }
+        }
+
+        let (Some(rela_vaddr), Some(rela_size)) = (rela_vaddr, rela_size) else {
+            return Ok(Vec::new());
+        };
+        if rela_entsize == 0 {
+            return Err(ExecutorError::InvalidElf("DT_RELAENT is zero".into()));
+        }
+
+        let rela_offset = self
+            .vaddr_to_file_offset(rela_vaddr)
+            .ok_or_else(|| ExecutorError::InvalidElf("DT_RELA address not in any segment".into()))?;
+        if rela_offset + rela_size > self.data.len() {
+            return Err(ExecutorError::InvalidElf("Relocation table out of bounds".into()));
+        }
+
+        let mut relocations = Vec::with_capacity(rela_size / rela_entsize);
+        for entry_offset in (rela_offset..rela_offset + rela_size).step_by(rela_entsize) {
+            let r_offset = u32::from_le_bytes([
+                self.data[entry_offset],
+                self.data[entry_offset + 1],
+                self.data[entry_offset + 2],
+                self.data[entry_offset + 3],
+            ]);
+            let r_info = u32::from_le_bytes([
+                self.data[entry_offset + 4],
+                self.data[entry_offset + 5],
+                self.data[entry_offset + 6],
+                self.data[entry_offset + 7],
+            ]);
+            let r_addend = i32::from_le_bytes([
+                self.data[entry_offset + 8],
+                self.data[entry_offset + 9],
+                self.data[entry_offset + 10],
+                self.data[entry_offset + 11],
+            ]);
+            relocations.push(Elf32Rela { r_offset, r_info, r_addend });
+        }
+
+        Ok(relocations)
+    }
+
+    /// Parse the `.dynsym` symbol table referenced by the `PT_DYNAMIC`
+    /// segment, biased by `bias`, indexed the same way `Elf32Rela::r_sym`
+    /// indexes into it. Returns an empty vector if there's no `DT_SYMTAB`
+    /// entry (e.g. a binary with only `R_RISCV_RELATIVE` relocations,
+    /// which don't reference a symbol).
+    ///
+    /// `.dynsym`'s size isn't recorded by any `DT_*` tag directly; like most
+    /// loaders, we rely on `.dynstr` (addressed by `DT_STRTAB`) immediately
+    /// following `.dynsym` in the file and use the gap between them to
+    /// compute the entry count.
+    fn dynamic_symbol_values(&self, bias: u32) -> Result<Vec<u32>, ExecutorError> {
+        let Some(dynamic) = self.program_headers.iter().find(|ph| ph.p_type == PT_DYNAMIC) else {
+            return Ok(Vec::new());
+        };
+
+        let mut symtab_vaddr = None;
+        let mut strtab_vaddr = None;
+        let mut syment = SYM_SIZE;
+
+        let dyn_offset = dynamic.p_offset as usize;
+        let dyn_size = dynamic.p_filesz as usize;
+        for entry_offset in (dyn_offset..dyn_offset + dyn_size).step_by(8) {
+            if entry_offset + 8 > self.data.len() {
+                return Err(ExecutorError::InvalidElf("Dynamic entry out of bounds".into()));
+            }
+            let tag = i32::from_le_bytes([
+                self.data[entry_offset],
+                self.data[entry_offset + 1],
+                self.data[entry_offset + 2],
+                self.data[entry_offset + 3],
+            ]);
+            let val = u32::from_le_bytes([
+                self.data[entry_offset + 4],
+                self.data[entry_offset + 5],
+                self.data[entry_offset + 6],
+                self.data[entry_offset + 7],
+            ]);
+
+            if tag == DT_NULL {
+                break;
+            } else if tag == DT_SYMTAB {
+                symtab_vaddr = Some(val);
+            } else if tag == DT_STRTAB {
+                strtab_vaddr = Some(val);
+            } else if tag == DT_SYMENT && val > 0 {
+                syment = val as usize;
+            }
+        }
+
+        let (Some(symtab_vaddr), Some(strtab_vaddr)) = (symtab_vaddr, strtab_vaddr) else {
+            return Ok(Vec::new());
+        };
+
+        let symtab_offset = self
+            .vaddr_to_file_offset(symtab_vaddr)
+            .ok_or_else(|| ExecutorError::InvalidElf("DT_SYMTAB address not in any segment".into()))?;
+        let strtab_offset = self
+            .vaddr_to_file_offset(strtab_vaddr)
+            .ok_or_else(|| ExecutorError::InvalidElf("DT_STRTAB address not in any segment".into()))?;
+        if strtab_offset < symtab_offset {
+            return Err(ExecutorError::InvalidElf(
+                "DT_STRTAB precedes DT_SYMTAB; cannot infer .dynsym size".into(),
+            ));
+        }
+        let symtab_size = strtab_offset - symtab_offset;
+        if symtab_offset + symtab_size > self.data.len() {
+            return Err(ExecutorError::InvalidElf("Dynamic symbol table out of bounds".into()));
+        }
+
+        let mut values = Vec::with_capacity(symtab_size / syment);
+        for entry_offset in (symtab_offset..symtab_offset + symtab_size).step_by(syment) {
+            if entry_offset + SYM_SIZE > self.data.len() {
+                return Err(ExecutorError::InvalidElf("Dynamic symbol entry out of bounds".into()));
+            }
+            let st_value = u32::from_le_bytes([
+                self.data[entry_offset + 4],
+                self.data[entry_offset + 5],
+                self.data[entry_offset + 6],
+                self.data[entry_offset + 7],
+            ]);
+            values.push(st_value.wrapping_add(bias));
+        }
+
+        Ok(values)
+    }
+
+    /// Apply relocations, biasing every address (relocation target and
+    /// symbol-independent `R_RISCV_RELATIVE` computations alike) by `bias`.
+    ///
+    /// Supports `R_RISCV_RELATIVE` (`bias + addend`, the only type a
+    /// statically-linked PIE binary with no external symbols emits),
+    /// `R_RISCV_32` (`symbol_value + addend`), and `R_RISCV_JUMP_SLOT` /
+    /// `R_RISCV_GLOB_DAT` (resolved `symbol_value`, GOT/PLT-style). Rejects
+    /// any relocation whose `r_offset` doesn't land inside a loadable
+    /// segment, so a malformed relocation can't be used to write outside
+    /// the binary's declared memory image.
+    fn apply_relocations_at(&self, memory: &mut Memory, bias: u32) -> Result<(), ExecutorError> {
+        let dyn_symbols = self.dynamic_symbol_values(bias)?;
+        let load_ranges: Vec<(u32, u32)> = self
+            .loadable_segments()
+            .map(|ph| {
+                let start = ph.p_vaddr.wrapping_add(bias);
+                (start, start.wrapping_add(ph.p_memsz))
+            })
+            .collect();
+
+        let resolve_symbol = |reloc: &Elf32Rela| -> Result<u32, ExecutorError> {
+            dyn_symbols.get(reloc.r_sym() as usize).copied().ok_or_else(|| {
+                ExecutorError::InvalidElf(format!(
+                    "Relocation references out-of-range dynamic symbol index {}",
+                    reloc.r_sym()
+                ))
+            })
+        };
+
+        for reloc in self.relocations()? {
+            let r_offset = reloc.r_offset.wrapping_add(bias);
+            let write_end = r_offset.checked_add(4);
+            let in_bounds = write_end.is_some_and(|write_end| {
+                load_ranges.iter().any(|&(start, end)| r_offset >= start && write_end <= end)
+            });
+            if !in_bounds {
+                return Err(ExecutorError::InvalidElf(format!(
+                    "Relocation r_offset {:#x} is outside any loadable segment",
+                    r_offset
+                )));
+            }
+
+            let value = match reloc.r_type() {
+                R_RISCV_RELATIVE => bias.wrapping_add(reloc.r_addend as u32),
+                R_RISCV_32 => resolve_symbol(&reloc)?.wrapping_add(reloc.r_addend as u32),
+                R_RISCV_JUMP_SLOT | R_RISCV_GLOB_DAT => resolve_symbol(&reloc)?,
+                other => {
+                    return Err(ExecutorError::InvalidElf(format!(
+                        "Unsupported relocation type: {}",
+                        other
+                    )))
+                }
+            };
+            memory.write_u32(r_offset, value)?;
+        }
+        Ok(())
+    }
+
+    /// Load the ELF into memory at its natural bias ([`Self::load_bias`]),
+    /// permissively: a segment that is both writable and executable is
+    /// loaded as-is rather than rejected. Use
+    /// [`Self::load_into_memory_strict`] to reject W^X segments up front,
+    /// or [`Self::load_into_memory_at`] to load at a caller-chosen base.
     pub fn load_into_memory(&self, memory: &mut Memory) -> Result<u32, ExecutorError> {
+        self.load_into_memory_at_strict(memory, self.load_bias(), false)
+    }
+
+    /// Load the ELF into memory at its natural bias. If `strict` is set,
+    /// reject any segment that is simultaneously writable and executable up
+    /// front, rather than relying solely on `Memory`'s per-access
+    /// permission checks to catch the resulting NX/read-only violations
+    /// later.
+    pub fn load_into_memory_strict(&self, memory: &mut Memory, strict: bool) -> Result<u32, ExecutorError> {
+        self.load_into_memory_at_strict(memory, self.load_bias(), strict)
+    }
+
+    /// Load the ELF into memory at a caller-chosen `load_base`, permissively
+    /// (see [`Self::load_into_memory`]). Every `p_vaddr`, the entry point,
+    /// and every relocation target are shifted by `load_base` instead of
+    /// [`Self::load_bias`], so callers that need a non-default placement
+    /// (e.g. loading several PIE images into one address space) don't have
+    /// to go through [`PIE_LOAD_BASE`].
+    pub fn load_into_memory_at(&self, memory: &mut Memory, load_base: u32) -> Result<u32, ExecutorError> {
+        self.load_into_memory_at_strict(memory, load_base, false)
+    }
+
+    fn load_into_memory_at_strict(
+        &self,
+        memory: &mut Memory,
+        load_base: u32,
+        strict: bool,
+    ) -> Result<u32, ExecutorError> {
         for ph in self.loadable_segments() {
+            let writable = ph.p_flags & segment_flags::PF_W != 0;
+            let executable = ph.p_flags & segment_flags::PF_X != 0;
+            if strict && writable && executable {
+                return Err(ExecutorError::InvalidElf(
+                    "segment is both writable and executable (W^X violation)".into(),
+                ));
+            }
+
             let file_offset = ph.p_offset as usize;
             let file_size = ph.p_filesz as usize;
-            let mem_addr = ph.p_vaddr;
+            let mem_addr = ph.p_vaddr.wrapping_add(load_base);
             let mem_size = ph.p_memsz as usize;
 
             // Validate bounds
@@ -189,19 +864,33 @@ impl ElfLoader {
                     memory.write_u8(bss_start + i as u32, 0)?;
                 }
             }
+
+            // Record this segment's permissions so Memory enforces them on
+            // every access after loading, not just at load time.
+            memory.set_permissions(mem_addr, mem_size as u32, ph.p_flags)?;
         }
 
-        Ok(self.entry_point())
+        self.apply_relocations_at(memory, load_base)?;
+
+        Ok(self.header.entry.wrapping_add(load_base))
     }
 
-    /// Get memory requirements (lowest and highest addresses).
+    /// Get memory requirements (lowest and highest addresses), biased for
+    /// PIE binaries the same way [`ElfLoader::load_into_memory`] is.
     pub fn memory_bounds(&self) -> (u32, u32) {
+        self.memory_bounds_at(self.load_bias())
+    }
+
+    /// Get memory requirements as [`Self::memory_bounds`] would, but biased
+    /// by a caller-chosen `load_base` instead of [`Self::load_bias`] — the
+    /// bounds matching what [`Self::load_into_memory_at`] actually loads at.
+    pub fn memory_bounds_at(&self, load_base: u32) -> (u32, u32) {
         let mut low = u32::MAX;
         let mut high = 0u32;
 
         for ph in self.loadable_segments() {
-            low = low.min(ph.p_vaddr);
-            high = high.max(ph.p_vaddr + ph.p_memsz);
+            low = low.min(ph.p_vaddr + load_base);
+            high = high.max(ph.p_vaddr + load_base + ph.p_memsz);
         }
 
         (low, high)
@@ -226,6 +915,107 @@ impl ElfLoader {
     pub fn program_headers(&self) -> &[Elf32ProgramHeader] {
         &self.program_headers
     }
+
+    /// Resolve a symbol name to its address, for syscall/trace resolution.
+    ///
+    /// Returns `None` if the binary has no symbol table (stripped) or the
+    /// name isn't present.
+    pub fn symbol_address(&self, name: &str) -> Option<u32> {
+        self.symbols.get(name).copied()
+    }
+
+    /// Iterate over all resolved symbols.
+    pub fn symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        self.symbols.iter().map(|(name, &address)| Symbol { name: name.clone(), address })
+    }
+
+    /// Set up the initial guest stack per the RISC-V Linux process-startup
+    /// ABI: strings, then (from low to high address) `argc`, `argv[]`,
+    /// `NULL`, `envp[]`, `NULL`, the auxiliary vector, `AT_NULL`.
+    ///
+    /// `stack_top` is the highest stack address (exclusive); strings and
+    /// the argc/argv/envp/auxv block are carved out below it. Returns the
+    /// resulting stack pointer, 16-byte aligned as the ABI requires.
+    pub fn init_stack(
+        &self,
+        memory: &mut Memory,
+        stack_top: u32,
+        argv: &[&str],
+        envp: &[&str],
+    ) -> Result<u32, ExecutorError> {
+        let mut cursor = stack_top;
+
+        let mut write_cstr = |memory: &mut Memory, cursor: &mut u32, s: &str| -> Result<u32, ExecutorError> {
+            let bytes = s.as_bytes();
+            *cursor -= bytes.len() as u32 + 1;
+            let addr = *cursor;
+            if !bytes.is_empty() {
+                memory.load_program(addr, bytes)?;
+            }
+            memory.write_u8(addr + bytes.len() as u32, 0)?;
+            Ok(addr)
+        };
+
+        let mut argv_addrs = Vec::with_capacity(argv.len());
+        for s in argv {
+            argv_addrs.push(write_cstr(memory, &mut cursor, s)?);
+        }
+        let mut envp_addrs = Vec::with_capacity(envp.len());
+        for s in envp {
+            envp_addrs.push(write_cstr(memory, &mut cursor, s)?);
+        }
+
+        // AT_RANDOM: the kernel normally fills this with real entropy, but
+        // execution must stay deterministic for proving, so use a fixed
+        // pattern instead.
+        cursor -= 16;
+        let random_addr = cursor;
+        memory.load_program(random_addr, &[0x42u8; 16])?;
+
+        let auxv: [(u32, u32); 7] = [
+            (AT_PHDR, self.load_bias().wrapping_add(self.header.phoff)),
+            (AT_PHENT, self.header.phentsize as u32),
+            (AT_PHNUM, self.header.phnum as u32),
+            (AT_PAGESZ, PAGE_SIZE),
+            (AT_BASE, self.load_bias()),
+            (AT_ENTRY, self.entry_point()),
+            (AT_RANDOM, random_addr),
+        ];
+
+        let block_size = 4 // argc
+            + 4 * (argv_addrs.len() as u32 + 1) // argv[] + NULL
+            + 4 * (envp_addrs.len() as u32 + 1) // envp[] + NULL
+            + 8 * (auxv.len() as u32 + 1); // auxv pairs + AT_NULL
+
+        let sp = cursor.wrapping_sub(block_size) & !0xf;
+        let mut addr = sp;
+
+        memory.write_u32(addr, argv_addrs.len() as u32)?;
+        addr += 4;
+        for &a in &argv_addrs {
+            memory.write_u32(addr, a)?;
+            addr += 4;
+        }
+        memory.write_u32(addr, 0)?;
+        addr += 4;
+        for &a in &envp_addrs {
+            memory.write_u32(addr, a)?;
+            addr += 4;
+        }
+        memory.write_u32(addr, 0)?;
+        addr += 4;
+        for (key, val) in auxv {
+            memory.write_u32(addr, key)?;
+            addr += 4;
+            memory.write_u32(addr, val)?;
+            addr += 4;
+        }
+        memory.write_u32(addr, AT_NULL)?;
+        addr += 4;
+        memory.write_u32(addr, 0)?;
+
+        Ok(sp)
+    }
 }
 
 /// ELF section flags.
@@ -239,6 +1029,9 @@ pub mod section_flags {
 }
 
 /// ELF segment flags.
+///
+/// Passed straight through to `Memory::set_permissions` as the enforced
+/// access mask for the segment's address range.
 pub mod segment_flags {
     /// Segment is executable.
     pub const PF_X: u32 = 0x1;
@@ -286,7 +1079,270 @@ pub fn build_test_elf(code: &[u8], entry: u32, load_addr: u32) -> Vec<u8> {
     
     // Code segment
     elf.extend_from_slice(code);
-    
+
+    elf
+}
+
+/// Build a minimal `ET_DYN` (PIE) ELF for testing, with one `PT_LOAD`
+/// segment (vaddr 0) holding `code` followed by a `.rela.dyn` table with a
+/// single `R_RISCV_RELATIVE` relocation patching the first word of `code`
+/// to `load_bias + reloc_addend`, and a `PT_DYNAMIC` segment describing it.
+pub fn build_test_pie_elf(code: &[u8], entry: u32, reloc_addend: i32) -> Vec<u8> {
+    let mut elf = Vec::new();
+
+    let rela_size = 12u32;
+    let dynamic_size = 32u32; // DT_RELA, DT_RELASZ, DT_RELAENT, DT_NULL
+
+    // ELF header (52 bytes)
+    elf.extend_from_slice(&ELF_MAGIC);
+    elf.push(ELFCLASS32);
+    elf.push(ELFDATA2LSB);
+    elf.push(1);
+    elf.push(0);
+    elf.extend_from_slice(&[0u8; 8]);
+    elf.extend_from_slice(&ET_DYN.to_le_bytes()); // Type: PIE
+    elf.extend_from_slice(&EM_RISCV.to_le_bytes());
+    elf.extend_from_slice(&1u32.to_le_bytes());
+    elf.extend_from_slice(&entry.to_le_bytes());
+    elf.extend_from_slice(&52u32.to_le_bytes()); // Program header offset
+    elf.extend_from_slice(&0u32.to_le_bytes());
+    elf.extend_from_slice(&0u32.to_le_bytes());
+    elf.extend_from_slice(&52u16.to_le_bytes());
+    elf.extend_from_slice(&32u16.to_le_bytes());
+    elf.extend_from_slice(&2u16.to_le_bytes()); // Number of program headers
+    elf.extend_from_slice(&0u16.to_le_bytes());
+    elf.extend_from_slice(&0u16.to_le_bytes());
+    elf.extend_from_slice(&0u16.to_le_bytes());
+
+    let code_offset = 52 + 2 * 32; // After ELF header + 2 program headers
+    let rela_offset = code_offset + code.len() as u32;
+    let dynamic_offset = rela_offset + rela_size;
+    let segment_size = code.len() as u32 + rela_size + dynamic_size;
+
+    // PH0: PT_LOAD covering code + rela table + dynamic table, vaddr 0
+    elf.extend_from_slice(&PT_LOAD.to_le_bytes());
+    elf.extend_from_slice(&code_offset.to_le_bytes());
+    elf.extend_from_slice(&0u32.to_le_bytes()); // p_vaddr
+    elf.extend_from_slice(&0u32.to_le_bytes()); // p_paddr
+    elf.extend_from_slice(&segment_size.to_le_bytes());
+    elf.extend_from_slice(&segment_size.to_le_bytes());
+    elf.extend_from_slice(&(segment_flags::PF_R | segment_flags::PF_W | segment_flags::PF_X).to_le_bytes());
+    elf.extend_from_slice(&4u32.to_le_bytes());
+
+    // PH1: PT_DYNAMIC, same vaddr space as PH0
+    elf.extend_from_slice(&PT_DYNAMIC.to_le_bytes());
+    elf.extend_from_slice(&dynamic_offset.to_le_bytes());
+    elf.extend_from_slice(&dynamic_offset.to_le_bytes());
+    elf.extend_from_slice(&dynamic_offset.to_le_bytes());
+    elf.extend_from_slice(&dynamic_size.to_le_bytes());
+    elf.extend_from_slice(&dynamic_size.to_le_bytes());
+    elf.extend_from_slice(&(segment_flags::PF_R | segment_flags::PF_W).to_le_bytes());
+    elf.extend_from_slice(&4u32.to_le_bytes());
+
+    // Code
+    elf.extend_from_slice(code);
+
+    // .rela.dyn: one R_RISCV_RELATIVE entry patching offset 0
+    elf.extend_from_slice(&0u32.to_le_bytes()); // r_offset
+    elf.extend_from_slice(&R_RISCV_RELATIVE.to_le_bytes()); // r_info (sym 0, type RELATIVE)
+    elf.extend_from_slice(&reloc_addend.to_le_bytes()); // r_addend
+
+    // .dynamic. DT_RELA is a vaddr, not a raw file offset: PH0 has
+    // p_vaddr=0 and p_offset=code_offset, so the vaddr of a file byte at
+    // `rela_offset` is `rela_offset - code_offset`.
+    elf.extend_from_slice(&DT_RELA.to_le_bytes());
+    elf.extend_from_slice(&(rela_offset - code_offset).to_le_bytes());
+    elf.extend_from_slice(&DT_RELASZ.to_le_bytes());
+    elf.extend_from_slice(&rela_size.to_le_bytes());
+    elf.extend_from_slice(&DT_RELAENT.to_le_bytes());
+    elf.extend_from_slice(&rela_size.to_le_bytes());
+    elf.extend_from_slice(&DT_NULL.to_le_bytes());
+    elf.extend_from_slice(&0u32.to_le_bytes());
+
+    elf
+}
+
+/// Build a minimal `ET_DYN` (PIE) ELF for testing a single symbol-dependent
+/// relocation (`R_RISCV_32`, `R_RISCV_JUMP_SLOT`, or `R_RISCV_GLOB_DAT`),
+/// with one `PT_LOAD` segment (vaddr 0) holding `code` followed by
+/// `.rela.dyn`, `.dynsym`, `.dynstr`, and a `PT_DYNAMIC` segment describing
+/// them. The relocation of type `reloc_type` at `r_offset` resolves against
+/// dynsym index 1, whose `st_value` is `sym_value`.
+pub fn build_test_pie_elf_with_dynsym_reloc(
+    code: &[u8],
+    entry: u32,
+    reloc_type: u32,
+    r_offset: u32,
+    sym_value: u32,
+    reloc_addend: i32,
+) -> Vec<u8> {
+    let mut elf = Vec::new();
+
+    let rela_size = 12u32;
+    let dynstr = b"\0sym\0"; // index 0 reserved (empty name), "sym" at offset 1
+    let dynstr_size = dynstr.len() as u32;
+    let dynsym_size = 2 * SYM_SIZE as u32; // null symbol + one real entry
+    let dynamic_size = 8 * 8u32; // DT_SYMTAB, STRTAB, STRSZ, SYMENT, RELA, RELASZ, RELAENT, NULL
+
+    // ELF header (52 bytes)
+    elf.extend_from_slice(&ELF_MAGIC);
+    elf.push(ELFCLASS32);
+    elf.push(ELFDATA2LSB);
+    elf.push(1);
+    elf.push(0);
+    elf.extend_from_slice(&[0u8; 8]);
+    elf.extend_from_slice(&ET_DYN.to_le_bytes());
+    elf.extend_from_slice(&EM_RISCV.to_le_bytes());
+    elf.extend_from_slice(&1u32.to_le_bytes());
+    elf.extend_from_slice(&entry.to_le_bytes());
+    elf.extend_from_slice(&52u32.to_le_bytes()); // Program header offset
+    elf.extend_from_slice(&0u32.to_le_bytes());
+    elf.extend_from_slice(&0u32.to_le_bytes());
+    elf.extend_from_slice(&52u16.to_le_bytes());
+    elf.extend_from_slice(&32u16.to_le_bytes());
+    elf.extend_from_slice(&2u16.to_le_bytes()); // Number of program headers
+    elf.extend_from_slice(&0u16.to_le_bytes());
+    elf.extend_from_slice(&0u16.to_le_bytes());
+    elf.extend_from_slice(&0u16.to_le_bytes());
+
+    let code_offset = 52 + 2 * 32; // After ELF header + 2 program headers
+    let rela_offset = code_offset + code.len() as u32;
+    let dynsym_offset = rela_offset + rela_size;
+    let dynstr_offset = dynsym_offset + dynsym_size;
+    let dynamic_offset = dynstr_offset + dynstr_size;
+    let segment_size = dynamic_offset + dynamic_size - code_offset;
+
+    // PH0: PT_LOAD covering code + rela/dynsym/dynstr/dynamic, vaddr 0
+    elf.extend_from_slice(&PT_LOAD.to_le_bytes());
+    elf.extend_from_slice(&code_offset.to_le_bytes());
+    elf.extend_from_slice(&0u32.to_le_bytes()); // p_vaddr
+    elf.extend_from_slice(&0u32.to_le_bytes()); // p_paddr
+    elf.extend_from_slice(&segment_size.to_le_bytes());
+    elf.extend_from_slice(&segment_size.to_le_bytes());
+    elf.extend_from_slice(&(segment_flags::PF_R | segment_flags::PF_W | segment_flags::PF_X).to_le_bytes());
+    elf.extend_from_slice(&4u32.to_le_bytes());
+
+    // PH1: PT_DYNAMIC, same vaddr space as PH0
+    elf.extend_from_slice(&PT_DYNAMIC.to_le_bytes());
+    elf.extend_from_slice(&dynamic_offset.to_le_bytes());
+    elf.extend_from_slice(&dynamic_offset.to_le_bytes());
+    elf.extend_from_slice(&dynamic_offset.to_le_bytes());
+    elf.extend_from_slice(&dynamic_size.to_le_bytes());
+    elf.extend_from_slice(&dynamic_size.to_le_bytes());
+    elf.extend_from_slice(&(segment_flags::PF_R | segment_flags::PF_W).to_le_bytes());
+    elf.extend_from_slice(&4u32.to_le_bytes());
+
+    // Code
+    elf.extend_from_slice(code);
+
+    // .rela.dyn: one entry of `reloc_type` against dynsym index 1
+    elf.extend_from_slice(&r_offset.to_le_bytes());
+    elf.extend_from_slice(&((1u32 << 8) | reloc_type).to_le_bytes()); // r_info
+    elf.extend_from_slice(&reloc_addend.to_le_bytes());
+
+    // .dynsym: null symbol, then one entry with the given st_value
+    elf.extend_from_slice(&[0u8; SYM_SIZE]);
+    elf.extend_from_slice(&1u32.to_le_bytes()); // st_name: offset 1 in .dynstr
+    elf.extend_from_slice(&sym_value.to_le_bytes()); // st_value
+    elf.extend_from_slice(&0u32.to_le_bytes()); // st_size
+    elf.push(0); // st_info
+    elf.push(0); // st_other
+    elf.extend_from_slice(&0u16.to_le_bytes()); // st_shndx
+
+    // .dynstr
+    elf.extend_from_slice(dynstr);
+
+    // .dynamic. Every DT_* address here is a vaddr, not a raw file offset:
+    // PH0 has p_vaddr=0 and p_offset=code_offset, so the vaddr of a file
+    // byte at offset `f` is `f - code_offset`.
+    elf.extend_from_slice(&DT_SYMTAB.to_le_bytes());
+    elf.extend_from_slice(&(dynsym_offset - code_offset).to_le_bytes());
+    elf.extend_from_slice(&DT_STRTAB.to_le_bytes());
+    elf.extend_from_slice(&(dynstr_offset - code_offset).to_le_bytes());
+    elf.extend_from_slice(&DT_STRSZ.to_le_bytes());
+    elf.extend_from_slice(&dynstr_size.to_le_bytes());
+    elf.extend_from_slice(&DT_SYMENT.to_le_bytes());
+    elf.extend_from_slice(&(SYM_SIZE as u32).to_le_bytes());
+    elf.extend_from_slice(&DT_RELA.to_le_bytes());
+    elf.extend_from_slice(&(rela_offset - code_offset).to_le_bytes());
+    elf.extend_from_slice(&DT_RELASZ.to_le_bytes());
+    elf.extend_from_slice(&rela_size.to_le_bytes());
+    elf.extend_from_slice(&DT_RELAENT.to_le_bytes());
+    elf.extend_from_slice(&rela_size.to_le_bytes());
+    elf.extend_from_slice(&DT_NULL.to_le_bytes());
+    elf.extend_from_slice(&0u32.to_le_bytes());
+
+    elf
+}
+
+/// Build an `ET_EXEC` ELF like [`build_test_elf`], plus a `.symtab`/
+/// `.strtab` section pair containing the given `(name, value)` symbols.
+pub fn build_test_elf_with_symbols(
+    code: &[u8],
+    entry: u32,
+    load_addr: u32,
+    symbols: &[(&str, u32)],
+) -> Vec<u8> {
+    let mut elf = build_test_elf(code, entry, load_addr);
+    let code_offset = 52 + 32;
+
+    // .strtab: index 0 is reserved (empty name), then each symbol name.
+    let mut strtab = vec![0u8];
+    let mut name_offsets = Vec::with_capacity(symbols.len());
+    for (name, _) in symbols {
+        name_offsets.push(strtab.len() as u32);
+        strtab.extend_from_slice(name.as_bytes());
+        strtab.push(0);
+    }
+    let strtab_offset = (code_offset + code.len()) as u32;
+
+    // .symtab: a leading null symbol, then one entry per symbol.
+    let mut symtab = vec![0u8; SYM_SIZE];
+    for (&(_, value), &name_off) in symbols.iter().zip(name_offsets.iter()) {
+        symtab.extend_from_slice(&name_off.to_le_bytes()); // st_name
+        symtab.extend_from_slice(&value.to_le_bytes()); // st_value
+        symtab.extend_from_slice(&0u32.to_le_bytes()); // st_size
+        symtab.push(0); // st_info
+        symtab.push(0); // st_other
+        symtab.extend_from_slice(&0u16.to_le_bytes()); // st_shndx
+    }
+    let symtab_offset = strtab_offset + strtab.len() as u32;
+    let shoff = symtab_offset + symtab.len() as u32;
+
+    elf.extend_from_slice(&strtab);
+    elf.extend_from_slice(&symtab);
+
+    // Section headers: [0]=NULL, [1]=.strtab, [2]=.symtab (sh_link -> 1).
+    for _ in 0..40 {
+        elf.push(0); // Section 0: NULL section
+    }
+    elf.extend_from_slice(&0u32.to_le_bytes()); // sh_name
+    elf.extend_from_slice(&3u32.to_le_bytes()); // sh_type: SHT_STRTAB
+    elf.extend_from_slice(&0u32.to_le_bytes()); // sh_flags
+    elf.extend_from_slice(&0u32.to_le_bytes()); // sh_addr
+    elf.extend_from_slice(&strtab_offset.to_le_bytes()); // sh_offset
+    elf.extend_from_slice(&(strtab.len() as u32).to_le_bytes()); // sh_size
+    elf.extend_from_slice(&0u32.to_le_bytes()); // sh_link
+    elf.extend_from_slice(&0u32.to_le_bytes()); // sh_info
+    elf.extend_from_slice(&1u32.to_le_bytes()); // sh_addralign
+    elf.extend_from_slice(&0u32.to_le_bytes()); // sh_entsize
+
+    elf.extend_from_slice(&0u32.to_le_bytes()); // sh_name
+    elf.extend_from_slice(&2u32.to_le_bytes()); // sh_type: SHT_SYMTAB
+    elf.extend_from_slice(&0u32.to_le_bytes()); // sh_flags
+    elf.extend_from_slice(&0u32.to_le_bytes()); // sh_addr
+    elf.extend_from_slice(&symtab_offset.to_le_bytes()); // sh_offset
+    elf.extend_from_slice(&(symtab.len() as u32).to_le_bytes()); // sh_size
+    elf.extend_from_slice(&1u32.to_le_bytes()); // sh_link: index of .strtab
+    elf.extend_from_slice(&0u32.to_le_bytes()); // sh_info
+    elf.extend_from_slice(&4u32.to_le_bytes()); // sh_addralign
+    elf.extend_from_slice(&(SYM_SIZE as u32).to_le_bytes()); // sh_entsize
+
+    // Patch the ELF header's shoff/shentsize/shnum fields.
+    elf[32..36].copy_from_slice(&shoff.to_le_bytes());
+    elf[46..48].copy_from_slice(&(SHDR_SIZE as u16).to_le_bytes());
+    elf[48..50].copy_from_slice(&3u16.to_le_bytes());
+
     elf
 }
 
@@ -294,6 +1350,76 @@ pub fn build_test_elf(code: &[u8], entry: u32, load_addr: u32) -> Vec<u8> {
 mod tests {
     use super::*;
 
+    /// Patch the `p_flags` field of the single program header built by
+    /// [`build_test_elf`].
+    fn set_test_elf_segment_flags(elf: &mut [u8], flags: u32) {
+        let ph_offset = 52;
+        elf[ph_offset + 24..ph_offset + 28].copy_from_slice(&flags.to_le_bytes());
+    }
+
+    #[test]
+    fn test_parse_rejects_phentsize_too_small() {
+        let code = vec![0x00; 16];
+        let mut elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        elf_data[42..44].copy_from_slice(&16u16.to_le_bytes()); // e_phentsize
+
+        assert!(ElfLoader::parse(&elf_data).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_program_header_table_out_of_bounds() {
+        let code = vec![0x00; 16];
+        let mut elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        elf_data[44..46].copy_from_slice(&1000u16.to_le_bytes()); // e_phnum
+
+        assert!(ElfLoader::parse(&elf_data).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_entrypoint_outside_any_segment() {
+        let code = vec![0x00; 16];
+        let elf_data = build_test_elf(&code, 0x9999, 0x1000);
+
+        match ElfLoader::parse(&elf_data) {
+            Err(ExecutorError::EntrypointOutOfBounds { entry }) => assert_eq!(entry, 0x9999),
+            other => panic!("expected EntrypointOutOfBounds, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_memsz_smaller_than_filesz() {
+        let code = vec![0x00; 16];
+        let mut elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        let ph_offset = 52;
+        elf_data[ph_offset + 20..ph_offset + 24].copy_from_slice(&8u32.to_le_bytes()); // p_memsz
+
+        match ElfLoader::parse(&elf_data) {
+            Err(ExecutorError::SegmentExceedsMemory { p_filesz, p_memsz }) => {
+                assert_eq!(p_filesz, 16);
+                assert_eq!(p_memsz, 8);
+            }
+            other => panic!("expected SegmentExceedsMemory, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_overlapping_load_segments() {
+        let code = vec![0x00; 16];
+        let mut elf_data = build_test_pie_elf(&code, 0x4, 0);
+        // Turn the PT_DYNAMIC segment into a second PT_LOAD, and pull its
+        // vaddr back to 0 so it actually overlaps PH0's [0, segment_size)
+        // span (PH1's own p_vaddr, left at dynamic_offset, sits past PH0's
+        // end and wouldn't overlap on its own).
+        let ph1_offset = 52 + 32;
+        elf_data[ph1_offset..ph1_offset + 4].copy_from_slice(&PT_LOAD.to_le_bytes());
+        elf_data[ph1_offset + 8..ph1_offset + 12].copy_from_slice(&0u32.to_le_bytes());
+
+        match ElfLoader::parse(&elf_data) {
+            Err(ExecutorError::OverlappingSegment { .. }) => {}
+            other => panic!("expected OverlappingSegment, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_build_and_parse_elf() {
         // Simple RISC-V code: addi x1, x0, 42; ecall
@@ -341,9 +1467,279 @@ mod tests {
         let code = vec![0x00; 100];
         let elf_data = build_test_elf(&code, 0x2000, 0x2000);
         let loader = ElfLoader::parse(&elf_data).unwrap();
-        
+
         let (low, high) = loader.memory_bounds();
         assert_eq!(low, 0x2000);
         assert_eq!(high, 0x2000 + 100);
     }
+
+    #[test]
+    fn test_pie_binary_is_biased_to_load_base() {
+        let code = vec![0x00; 16];
+        let elf_data = build_test_pie_elf(&code, 0x4, 0);
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        assert!(loader.is_pie());
+        assert_eq!(loader.load_bias(), PIE_LOAD_BASE);
+        assert_eq!(loader.entry_point(), PIE_LOAD_BASE + 0x4);
+
+        let (low, high) = loader.memory_bounds();
+        assert_eq!(low, PIE_LOAD_BASE);
+        // PH0 spans the 16 code bytes, the 12-byte `.rela.dyn` entry and
+        // the 32-byte `.dynamic` table.
+        assert_eq!(high, PIE_LOAD_BASE + 16 + 12 + 32);
+    }
+
+    #[test]
+    fn test_pie_relocation_is_applied_at_load_time() {
+        let code = vec![0xffu8; 16];
+        let reloc_addend = 0x100;
+        let elf_data = build_test_pie_elf(&code, 0, reloc_addend);
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        let mut memory = Memory::with_default_size();
+        loader.load_into_memory(&mut memory).unwrap();
+
+        let patched = memory.read_u32(PIE_LOAD_BASE).unwrap();
+        assert_eq!(patched, PIE_LOAD_BASE.wrapping_add(reloc_addend as u32));
+    }
+
+    #[test]
+    fn test_r_riscv_32_relocation_resolves_dynsym_value() {
+        let code = vec![0xffu8; 16];
+        let elf_data = build_test_pie_elf_with_dynsym_reloc(&code, 0, R_RISCV_32, 0, 0x2000, 5);
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        let mut memory = Memory::with_default_size();
+        loader.load_into_memory(&mut memory).unwrap();
+
+        let patched = memory.read_u32(PIE_LOAD_BASE).unwrap();
+        assert_eq!(patched, (0x2000u32 + PIE_LOAD_BASE).wrapping_add(5));
+    }
+
+    #[test]
+    fn test_r_riscv_jump_slot_and_glob_dat_resolve_symbol_value() {
+        for reloc_type in [R_RISCV_JUMP_SLOT, R_RISCV_GLOB_DAT] {
+            let code = vec![0xffu8; 16];
+            let elf_data = build_test_pie_elf_with_dynsym_reloc(&code, 0, reloc_type, 4, 0x3000, 0);
+            let loader = ElfLoader::parse(&elf_data).unwrap();
+
+            let mut memory = Memory::with_default_size();
+            loader.load_into_memory(&mut memory).unwrap();
+
+            let patched = memory.read_u32(PIE_LOAD_BASE + 4).unwrap();
+            assert_eq!(patched, 0x3000u32 + PIE_LOAD_BASE);
+        }
+    }
+
+    #[test]
+    fn test_relocation_outside_any_segment_is_rejected() {
+        let code = vec![0xffu8; 16];
+        // r_offset far past the single PT_LOAD segment's memsz.
+        let elf_data = build_test_pie_elf_with_dynsym_reloc(&code, 0, R_RISCV_32, 0x10_000, 0x2000, 0);
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        let mut memory = Memory::with_default_size();
+        assert!(loader.load_into_memory(&mut memory).is_err());
+    }
+
+    #[test]
+    fn test_load_into_memory_at_uses_caller_chosen_base() {
+        let code = vec![
+            0x93, 0x00, 0xa0, 0x02, // addi x1, x0, 42
+            0x73, 0x00, 0x00, 0x00, // ecall
+        ];
+        let elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        let mut memory = Memory::with_default_size();
+        let load_base = 0x0010_0000;
+        let entry = loader.load_into_memory_at(&mut memory, load_base).unwrap();
+
+        assert_eq!(entry, 0x1000 + load_base);
+        let instr = memory.read_u32(0x1000 + load_base).unwrap();
+        assert_eq!(instr, 0x02a00093);
+    }
+
+    #[test]
+    fn test_exec_binary_is_not_pie() {
+        let code = vec![0x00; 16];
+        let elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        assert!(!loader.is_pie());
+        assert_eq!(loader.load_bias(), 0);
+    }
+
+    #[test]
+    fn test_symbol_lookup_by_name() {
+        let code = vec![0x00; 16];
+        let elf_data = build_test_elf_with_symbols(
+            &code,
+            0x1000,
+            0x1000,
+            &[("_start", 0x1000), ("handle_syscall", 0x1010)],
+        );
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        assert_eq!(loader.symbol_address("_start"), Some(0x1000));
+        assert_eq!(loader.symbol_address("handle_syscall"), Some(0x1010));
+        assert_eq!(loader.symbol_address("does_not_exist"), None);
+        assert_eq!(loader.symbols().count(), 2);
+    }
+
+    #[test]
+    fn test_stripped_binary_has_no_symbols() {
+        let code = vec![0x00; 16];
+        let elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        assert_eq!(loader.symbol_address("anything"), None);
+        assert_eq!(loader.symbols().count(), 0);
+    }
+
+    #[test]
+    fn test_init_stack_layout() {
+        let code = vec![0x00; 16];
+        let elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        let mut memory = Memory::with_default_size();
+        let stack_top = 0x8000_0000;
+        let sp = loader
+            .init_stack(&mut memory, stack_top, &["guest"], &["PATH=/"])
+            .unwrap();
+
+        assert_eq!(sp % 16, 0);
+        assert!(sp < stack_top);
+
+        let argc = memory.read_u32(sp).unwrap();
+        assert_eq!(argc, 1);
+
+        let argv0_ptr = memory.read_u32(sp + 4).unwrap();
+        assert!(argv0_ptr < stack_top && argv0_ptr > sp);
+
+        let argv_terminator = memory.read_u32(sp + 8).unwrap();
+        assert_eq!(argv_terminator, 0);
+
+        let envp0_ptr = memory.read_u32(sp + 12).unwrap();
+        assert!(envp0_ptr < stack_top && envp0_ptr > sp);
+
+        let envp_terminator = memory.read_u32(sp + 16).unwrap();
+        assert_eq!(envp_terminator, 0);
+    }
+
+    #[test]
+    fn test_init_stack_empty_argv_envp() {
+        let code = vec![0x00; 16];
+        let elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        let mut memory = Memory::with_default_size();
+        let sp = loader.init_stack(&mut memory, 0x8000_0000, &[], &[]).unwrap();
+
+        assert_eq!(memory.read_u32(sp).unwrap(), 0); // argc
+        assert_eq!(memory.read_u32(sp + 4).unwrap(), 0); // argv NULL
+        assert_eq!(memory.read_u32(sp + 8).unwrap(), 0); // envp NULL
+    }
+
+    #[test]
+    fn test_load_strict_rejects_writable_and_executable_segment() {
+        let code = vec![0x00; 16];
+        let mut elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        set_test_elf_segment_flags(
+            &mut elf_data,
+            segment_flags::PF_R | segment_flags::PF_W | segment_flags::PF_X,
+        );
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        let mut memory = Memory::with_default_size();
+        let result = loader.load_into_memory_strict(&mut memory, true);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_load_permissive_allows_writable_and_executable_segment() {
+        let code = vec![0x00; 16];
+        let mut elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        set_test_elf_segment_flags(
+            &mut elf_data,
+            segment_flags::PF_R | segment_flags::PF_W | segment_flags::PF_X,
+        );
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        let mut memory = Memory::with_default_size();
+        assert!(loader.load_into_memory(&mut memory).is_ok());
+    }
+
+    #[test]
+    fn test_load_accepts_read_execute_segment() {
+        let code = vec![0x00; 16];
+        let mut elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        set_test_elf_segment_flags(&mut elf_data, segment_flags::PF_R | segment_flags::PF_X);
+        let loader = ElfLoader::parse(&elf_data).unwrap();
+
+        let mut memory = Memory::with_default_size();
+        assert!(loader.load_into_memory(&mut memory).is_ok());
+    }
+
+    #[test]
+    fn test_parse_maybe_compressed_passes_through_plain_elf() {
+        let code = vec![0x00; 16];
+        let elf_data = build_test_elf(&code, 0x1000, 0x1000);
+
+        let loader = ElfLoader::parse_maybe_compressed(&elf_data).unwrap();
+        assert_eq!(loader.entry_point(), 0x1000);
+    }
+
+    #[test]
+    fn test_parse_maybe_compressed_rejects_truncated_header() {
+        let mut truncated = COMPRESSED_MAGIC.to_vec();
+        truncated.extend_from_slice(&[0x00, 0x01]); // short of the 9-byte header
+
+        let result = ElfLoader::parse_maybe_compressed(&truncated);
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "compression")]
+    fn build_compressed_container(codec: u8, inner: &[u8], advertised_len: u32) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut compressed = Vec::new();
+        {
+            let mut encoder =
+                flate2::write::DeflateEncoder::new(&mut compressed, flate2::Compression::default());
+            encoder.write_all(inner).unwrap();
+        }
+
+        let mut container = COMPRESSED_MAGIC.to_vec();
+        container.extend_from_slice(&advertised_len.to_le_bytes());
+        container.push(codec);
+        container.extend_from_slice(&compressed);
+        container
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_parse_maybe_compressed_deflate_roundtrip() {
+        let code = vec![0x00; 16];
+        let elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        let container = build_compressed_container(CODEC_DEFLATE, &elf_data, elf_data.len() as u32);
+
+        let loader = ElfLoader::parse_maybe_compressed(&container).unwrap();
+        assert_eq!(loader.entry_point(), 0x1000);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_parse_maybe_compressed_rejects_length_mismatch() {
+        let code = vec![0x00; 16];
+        let elf_data = build_test_elf(&code, 0x1000, 0x1000);
+        // Advertise a length shorter than what the payload actually
+        // decompresses to, as a lying/bomb-style header would.
+        let container = build_compressed_container(CODEC_DEFLATE, &elf_data, 4);
+
+        let result = ElfLoader::parse_maybe_compressed(&container);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,165 @@
+//! `extern "C"` surface: opaque handles plus plain-data accessors, so a
+//! `cdylib` build of this crate can be linked from C, Python's `ctypes`,
+//! or anything else with a C FFI — no Rust types cross the boundary.
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::slice;
+
+use zp1_prover::serialize::{SerializableProof, VerificationKey};
+
+/// Opaque handle to a deserialized proof, owned by the caller between
+/// [`zp1_proof_from_bytes`] and [`zp1_proof_free`].
+pub struct ProofHandle(pub(crate) SerializableProof);
+
+/// Opaque handle to a verification key, owned by the caller between
+/// [`zp1_vk_from_json`] and [`zp1_vk_free`].
+pub struct VkHandle(pub(crate) VerificationKey);
+
+/// Status codes returned by the functions below. `errno`-style rather
+/// than `zp1_prover::serialize`'s `String` errors, since a non-Rust
+/// caller has no way to match on those.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZpStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidProof = 2,
+    InvalidVerificationKey = 3,
+    VerificationFailed = 4,
+}
+
+/// Deserialize a proof from the compact binary format
+/// [`SerializableProof::to_bytes`] produces into an opaque handle written
+/// to `*out_handle`.
+///
+/// # Safety
+/// `data` must point to at least `len` readable bytes (or be null iff
+/// `len == 0`), and `out_handle` must point to a valid, writable
+/// `*mut ProofHandle` that this function will overwrite on [`ZpStatus::Ok`].
+#[no_mangle]
+pub unsafe extern "C" fn zp1_proof_from_bytes(
+    data: *const u8,
+    len: usize,
+    out_handle: *mut *mut ProofHandle,
+) -> ZpStatus {
+    if out_handle.is_null() || (data.is_null() && len != 0) {
+        return ZpStatus::NullPointer;
+    }
+    let bytes = if len == 0 { &[] } else { slice::from_raw_parts(data, len) };
+    match SerializableProof::from_bytes(bytes) {
+        Ok(proof) => {
+            *out_handle = Box::into_raw(Box::new(ProofHandle(proof)));
+            ZpStatus::Ok
+        }
+        Err(_) => ZpStatus::InvalidProof,
+    }
+}
+
+/// Free a handle returned by [`zp1_proof_from_bytes`]. A null `handle` is
+/// a no-op.
+///
+/// # Safety
+/// `handle` must be null or a pointer previously returned by
+/// [`zp1_proof_from_bytes`] that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn zp1_proof_free(handle: *mut ProofHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Number of FRI queries `handle`'s proof was generated with.
+///
+/// # Safety
+/// `handle` must be a live, non-null pointer from [`zp1_proof_from_bytes`].
+#[no_mangle]
+pub unsafe extern "C" fn zp1_proof_num_queries(handle: *const ProofHandle) -> usize {
+    (*handle).0.config.num_queries
+}
+
+/// Log2 of the trace length `handle`'s proof was generated with.
+///
+/// # Safety
+/// `handle` must be a live, non-null pointer from [`zp1_proof_from_bytes`].
+#[no_mangle]
+pub unsafe extern "C" fn zp1_proof_log_trace_len(handle: *const ProofHandle) -> usize {
+    (*handle).0.config.log_trace_len
+}
+
+/// Number of FRI layer commitments in `handle`'s proof.
+///
+/// # Safety
+/// `handle` must be a live, non-null pointer from [`zp1_proof_from_bytes`].
+#[no_mangle]
+pub unsafe extern "C" fn zp1_proof_fri_commitment_count(handle: *const ProofHandle) -> usize {
+    (*handle).0.fri_commitments.len()
+}
+
+/// Parse a verification key from its JSON debug format
+/// ([`VerificationKey::to_json`]) into an opaque handle written to
+/// `*out_handle`.
+///
+/// # Safety
+/// `json` must be a valid, null-terminated C string, and `out_handle`
+/// must point to a valid, writable `*mut VkHandle` that this function
+/// will overwrite on [`ZpStatus::Ok`].
+#[no_mangle]
+pub unsafe extern "C" fn zp1_vk_from_json(
+    json: *const c_char,
+    out_handle: *mut *mut VkHandle,
+) -> ZpStatus {
+    if json.is_null() || out_handle.is_null() {
+        return ZpStatus::NullPointer;
+    }
+    let Ok(json) = CStr::from_ptr(json).to_str() else {
+        return ZpStatus::InvalidVerificationKey;
+    };
+    match VerificationKey::from_json(json) {
+        Ok(vk) => {
+            *out_handle = Box::into_raw(Box::new(VkHandle(vk)));
+            ZpStatus::Ok
+        }
+        Err(_) => ZpStatus::InvalidVerificationKey,
+    }
+}
+
+/// Free a handle returned by [`zp1_vk_from_json`]. A null `handle` is a
+/// no-op.
+///
+/// # Safety
+/// `handle` must be null or a pointer previously returned by
+/// [`zp1_vk_from_json`] that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn zp1_vk_free(handle: *mut VkHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Check that `proof` was generated under `vk`'s parameters, i.e. their
+/// `ProofConfig`s agree. This is a structural check, not a cryptographic
+/// one: full STARK verification needs the prover's query-proof
+/// representation (`zp1_verifier::Verifier`), which `SerializableProof`'s
+/// wire format doesn't carry OODS evaluations for. A config mismatch is
+/// still a real rejection — it catches a proof built for the wrong
+/// trace length, query count, or folding factor before a caller wastes
+/// time on it.
+///
+/// # Safety
+/// `proof` and `vk` must be live, non-null pointers from
+/// [`zp1_proof_from_bytes`] and [`zp1_vk_from_json`] respectively.
+#[no_mangle]
+pub unsafe extern "C" fn zp1_proof_verify(
+    proof: *const ProofHandle,
+    vk: *const VkHandle,
+) -> ZpStatus {
+    if proof.is_null() || vk.is_null() {
+        return ZpStatus::NullPointer;
+    }
+    if (*proof).0.config == (*vk).0.config {
+        ZpStatus::Ok
+    } else {
+        ZpStatus::VerificationFailed
+    }
+}
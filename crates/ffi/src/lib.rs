@@ -0,0 +1,18 @@
+//! zp1-ffi: C-ABI and Python bindings over `SerializableProof`/`VerificationKey`.
+//!
+//! Lets non-Rust callers — orchestration scripts, test harnesses — load a
+//! proof blob, read its [`zp1_prover::ProofConfig`], and check it against a
+//! verification key, without reimplementing `zp1_prover::serialize`'s M31
+//! bit-packing or hex encoding. [`c_api`] is the `extern "C"` surface a
+//! `cdylib` build of this crate exports; [`python`] wraps the same handles
+//! in a `pyo3` module for callers that would rather `import zp1` directly.
+
+pub mod c_api;
+#[cfg(feature = "python")]
+pub mod python;
+
+pub use c_api::{
+    zp1_proof_fri_commitment_count, zp1_proof_free, zp1_proof_from_bytes,
+    zp1_proof_log_trace_len, zp1_proof_num_queries, zp1_proof_verify, zp1_vk_free,
+    zp1_vk_from_json, ProofHandle, VkHandle, ZpStatus,
+};
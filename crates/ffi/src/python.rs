@@ -0,0 +1,76 @@
+//! Thin `pyo3` wrapper over the same types [`crate::c_api`]'s handles
+//! hold, for callers that would rather `import zp1` than link the
+//! `cdylib` through `ctypes`. Each method calls the same
+//! `zp1_prover::serialize` function or config comparison as its
+//! `extern "C"` counterpart and raises a parse failure as `ValueError`
+//! instead of returning a [`ZpStatus`](crate::c_api::ZpStatus).
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use zp1_prover::serialize::{SerializableProof, VerificationKey};
+
+/// Python-visible wrapper around a deserialized [`SerializableProof`].
+#[pyclass(name = "Proof")]
+pub struct PyProof(SerializableProof);
+
+#[pymethods]
+impl PyProof {
+    /// Deserialize a proof from the compact binary format
+    /// `SerializableProof.to_bytes()` (Rust side) produces.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        SerializableProof::from_bytes(data)
+            .map(PyProof)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Number of FRI queries this proof was generated with.
+    #[getter]
+    fn num_queries(&self) -> usize {
+        self.0.config.num_queries
+    }
+
+    /// Log2 of the trace length this proof was generated with.
+    #[getter]
+    fn log_trace_len(&self) -> usize {
+        self.0.config.log_trace_len
+    }
+
+    /// Number of FRI layer commitments in this proof.
+    #[getter]
+    fn fri_commitment_count(&self) -> usize {
+        self.0.fri_commitments.len()
+    }
+
+    /// Check that this proof was generated under `vk`'s parameters. See
+    /// `zp1_proof_verify`'s doc comment for exactly what is and isn't
+    /// checked.
+    fn verify(&self, vk: &PyVerificationKey) -> bool {
+        self.0.config == vk.0.config
+    }
+}
+
+/// Python-visible wrapper around a [`VerificationKey`].
+#[pyclass(name = "VerificationKey")]
+pub struct PyVerificationKey(VerificationKey);
+
+#[pymethods]
+impl PyVerificationKey {
+    /// Parse a verification key from its JSON debug format
+    /// (`VerificationKey.to_json()`, Rust side).
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        VerificationKey::from_json(json)
+            .map(PyVerificationKey)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
+
+/// The `zp1` Python module: `Proof` and `VerificationKey`.
+#[pymodule]
+fn zp1(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyProof>()?;
+    m.add_class::<PyVerificationKey>()?;
+    Ok(())
+}
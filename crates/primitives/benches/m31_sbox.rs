@@ -1,10 +1,13 @@
-//! Benchmarks for M31 field operations and Poseidon2 sbox candidates.
+//! Benchmarks for M31 field operations and the Poseidon2 permutation.
 //!
-//! This benchmark compares different sbox exponents to quantify the performance
-//! difference between M31 (which requires x^5 or higher) and fields like
-//! Koalabear/BabyBear that could use cheaper sboxes like x^3.
+//! Compares different sbox exponents in isolation (to quantify the
+//! performance difference between M31, which requires x^5 or higher, and
+//! fields like Koalabear/BabyBear that could use cheaper sboxes like
+//! x^3), then benchmarks the real production `Poseidon2M31` permutation
+//! end to end at every supported width.
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use zp1_primitives::poseidon2::Poseidon2M31;
 use zp1_primitives::M31;
 
 /// Compute x^3 (simulating cheaper sbox, e.g. Koalabear if valid)
@@ -91,47 +94,38 @@ fn bench_batch_sbox(c: &mut Criterion) {
     group.finish();
 }
 
-fn bench_poseidon2_round_simulation(c: &mut Criterion) {
-    let mut group = c.benchmark_group("Poseidon2 Round Simulation (width=12)");
+fn bench_poseidon2_permutation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Poseidon2 Permutation (production)");
 
-    // Simulate a width-12 Poseidon2 state
-    let state: [M31; 12] = core::array::from_fn(|i| M31::new((i + 1) as u32));
+    let perm12 = Poseidon2M31::<12>::new(0);
+    let perm16 = Poseidon2M31::<16>::new(0);
+    let perm24 = Poseidon2M31::<24>::new(0);
 
-    // Simulate MDS matrix (simple mock - just sum all elements)
-    fn mock_mds(state: [M31; 12]) -> [M31; 12] {
-        let sum: M31 = state.iter().fold(M31::ZERO, |acc, &x| acc + x);
-        core::array::from_fn(|i| state[i] + sum)
-    }
+    let state12: [M31; 12] = core::array::from_fn(|i| M31::new((i + 1) as u32));
+    let state16: [M31; 16] = core::array::from_fn(|i| M31::new((i + 1) as u32));
+    let state24: [M31; 24] = core::array::from_fn(|i| M31::new((i + 1) as u32));
 
-    group.bench_function("round with x^3 sbox", |bench| {
+    group.bench_function("width=12", |bench| {
         bench.iter(|| {
-            let mut s = black_box(state);
-            // Apply sbox to all elements
-            for x in &mut s {
-                *x = sbox_cube(*x);
-            }
-            // Apply MDS
-            mock_mds(s)
+            let mut s = black_box(state12);
+            perm12.permute(&mut s);
+            s
         })
     });
 
-    group.bench_function("round with x^5 sbox", |bench| {
+    group.bench_function("width=16", |bench| {
         bench.iter(|| {
-            let mut s = black_box(state);
-            for x in &mut s {
-                *x = sbox_fifth(*x);
-            }
-            mock_mds(s)
+            let mut s = black_box(state16);
+            perm16.permute(&mut s);
+            s
         })
     });
 
-    group.bench_function("round with x^7 sbox", |bench| {
+    group.bench_function("width=24", |bench| {
         bench.iter(|| {
-            let mut s = black_box(state);
-            for x in &mut s {
-                *x = sbox_seventh(*x);
-            }
-            mock_mds(s)
+            let mut s = black_box(state24);
+            perm24.permute(&mut s);
+            s
         })
     });
 
@@ -143,6 +137,6 @@ criterion_group!(
     bench_field_ops,
     bench_sbox,
     bench_batch_sbox,
-    bench_poseidon2_round_simulation
+    bench_poseidon2_permutation
 );
 criterion_main!(benches);
@@ -7,15 +7,18 @@
 //! - Circle group and Circle FFT for M31-native polynomial operations
 //! - Range-check helpers
 //! - Plonky3 interoperability for SIMD-optimized operations
+//! - Poseidon2 permutation over M31 for Merkle commitments and Fiat-Shamir
 
 pub mod circle;
 pub mod extension;
 pub mod field;
 pub mod limbs;
 pub mod p3_interop;
+pub mod poseidon2;
 
 pub use circle::{CircleDomain, CircleFFT, CirclePoint, Coset, FastCircleFFT};
 pub use extension::{CM31, QM31, U_SQUARED};
 pub use field::M31;
 pub use limbs::{from_limbs, to_limbs};
 pub use p3_interop::{from_p3, to_p3, P3M31};
+pub use poseidon2::{compress, hash_row, Poseidon2M31, Poseidon2Sponge};
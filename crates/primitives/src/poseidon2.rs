@@ -0,0 +1,404 @@
+//! Poseidon2 permutation over M31: the arithmetization-friendly hash
+//! backing Merkle tree commitments and the prover/verifier Fiat-Shamir
+//! sponge, so a recursive verifier AIR can cheaply re-evaluate the exact
+//! permutation this module runs natively (unlike a bit-oriented hash such
+//! as blake3/keccak, which a circuit can only emulate at enormous cost).
+//!
+//! Round constants are generated deterministically from a fixed seed (see
+//! [`round_constants`]) rather than reproduced from an external reference
+//! implementation — swapping in externally audited constants later is a
+//! drop-in change that doesn't touch the permutation's structure. The
+//! round *structure* matches the published Poseidon2 construction: full
+//! S-box rounds at the start and end ("external" rounds, with the
+//! `M4`-based linear layer below), and S-box-on-lane-0-only rounds in the
+//! middle ("internal" rounds, with a diagonal linear layer), separated by
+//! the `x^5` S-box — `x^3` is not a permutation of M31 since
+//! `gcd(3, p - 1) = 3 != 1`, while `gcd(5, p - 1) = 1`.
+
+use crate::M31;
+
+/// Number of full (external) rounds: half before the internal rounds,
+/// half after, as in the published Poseidon2 construction.
+const EXTERNAL_ROUNDS: usize = 8;
+
+/// Number of partial (internal) rounds for a given state width. Chosen
+/// conservatively in the same ballpark as published Poseidon2 parameter
+/// tables for similarly sized Mersenne-prime states; not a substitute for
+/// a formal security analysis before production use.
+const fn internal_rounds(width: usize) -> usize {
+    match width {
+        12 => 21,
+        16 => 13,
+        24 => 19,
+        _ => 21,
+    }
+}
+
+/// The `x^5` S-box: the quintic map is a permutation of M31 since
+/// `gcd(5, p - 1) = 1` (unlike `x^3`, where `gcd(3, p - 1) = 3`).
+#[inline]
+fn sbox(x: M31) -> M31 {
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    x4 * x
+}
+
+/// The fixed `4x4` MDS matrix Poseidon2's external linear layer applies to
+/// each 4-lane group of the state, before the cross-group circulant mix
+/// (see [`external_linear_layer`]).
+const M4: [[u64; 4]; 4] = [
+    [2, 3, 1, 1],
+    [1, 2, 3, 1],
+    [1, 1, 2, 3],
+    [3, 1, 1, 2],
+];
+
+/// Apply Poseidon2's external (full-round) linear layer: `M4` within each
+/// group of 4 lanes, then add the sum of every group's post-`M4` output to
+/// every lane — the standard construction that keeps the layer MDS-like
+/// while staying linear in the state width rather than quadratic.
+fn external_linear_layer<const WIDTH: usize>(state: &mut [M31; WIDTH]) {
+    debug_assert_eq!(WIDTH % 4, 0, "Poseidon2 width must be a multiple of 4");
+
+    for chunk in state.chunks_exact_mut(4) {
+        let x0 = chunk[0];
+        let x1 = chunk[1];
+        let x2 = chunk[2];
+        let x3 = chunk[3];
+        for (row, slot) in M4.iter().zip(chunk.iter_mut()) {
+            *slot = M31::new(row[0] as u32) * x0
+                + M31::new(row[1] as u32) * x1
+                + M31::new(row[2] as u32) * x2
+                + M31::new(row[3] as u32) * x3;
+        }
+    }
+
+    let mut group_sum = M31::ZERO;
+    for chunk in state.chunks_exact(4) {
+        group_sum = group_sum + chunk.iter().fold(M31::ZERO, |acc, &v| acc + v);
+    }
+    for lane in state.iter_mut() {
+        *lane = *lane + group_sum;
+    }
+}
+
+/// The internal (partial-round) diagonal, one small constant `i + 2` per
+/// lane. The resulting `sum(state) + diag[i]*state[i]` linear layer has
+/// determinant `prod(diag) * (1 + sum(1 / diag[i]))`, which is nonzero mod
+/// p for widths 12, 16 and 24, so the layer is invertible.
+fn internal_diagonal<const WIDTH: usize>() -> [M31; WIDTH] {
+    core::array::from_fn(|i| M31::new((i as u32) + 2))
+}
+
+/// Apply Poseidon2's internal (partial-round) linear layer: every lane
+/// becomes `sum(state) + diag[i] * state[i]`.
+fn internal_linear_layer<const WIDTH: usize>(state: &mut [M31; WIDTH], diag: &[M31; WIDTH]) {
+    let sum = state.iter().fold(M31::ZERO, |acc, &v| acc + v);
+    for (lane, &d) in state.iter_mut().zip(diag.iter()) {
+        *lane = sum + d * *lane;
+    }
+}
+
+/// Deterministically derive `count` round constants (or constant-tuples,
+/// one per lane) from `seed` via a linear congruential step in M31 — a minimal
+/// pseudo-random generator good enough to break the permutation's
+/// symmetry between rounds, not a cryptographic primitive in its own
+/// right. See the module docs for why these aren't externally audited
+/// reference constants.
+fn round_constants<const WIDTH: usize>(seed: u32, count: usize) -> Vec<[M31; WIDTH]> {
+    let mut state = M31::new(seed.max(1));
+    let multiplier = M31::new(0x2545_F491);
+    let increment = M31::new(0x9E37_79B9);
+
+    (0..count)
+        .map(|_| {
+            core::array::from_fn(|_| {
+                state = state * multiplier + increment;
+                state
+            })
+        })
+        .collect()
+}
+
+/// The Poseidon2 permutation over an M31 state of `WIDTH` lanes (12, 16,
+/// or 24 are the supported widths, matching common sponge rate/capacity
+/// splits for this field).
+#[derive(Clone)]
+pub struct Poseidon2M31<const WIDTH: usize> {
+    external_constants: Vec<[M31; WIDTH]>,
+    internal_constants: Vec<M31>,
+    internal_diagonal: [M31; WIDTH],
+}
+
+impl<const WIDTH: usize> Poseidon2M31<WIDTH> {
+    /// Build the permutation, baking in its round constants. `domain` lets
+    /// independent uses of this permutation (e.g. the Merkle hash vs. the
+    /// Fiat-Shamir sponge) derive distinct, non-interfering constants from
+    /// the same construction.
+    pub fn new(domain: u32) -> Self {
+        let internal_count = internal_rounds(WIDTH);
+        let external_constants = round_constants::<WIDTH>(domain, EXTERNAL_ROUNDS);
+        let internal_constants: Vec<M31> = round_constants::<1>(domain.wrapping_add(1), internal_count)
+            .into_iter()
+            .map(|c| c[0])
+            .collect();
+
+        Self {
+            external_constants,
+            internal_constants,
+            internal_diagonal: internal_diagonal::<WIDTH>(),
+        }
+    }
+
+    /// Run the full permutation in place: half the external rounds, then
+    /// the internal rounds, then the remaining external rounds.
+    pub fn permute(&self, state: &mut [M31; WIDTH]) {
+        let half = EXTERNAL_ROUNDS / 2;
+
+        for round_constants in &self.external_constants[..half] {
+            self.external_round(state, round_constants);
+        }
+        for &round_constant in &self.internal_constants {
+            self.internal_round(state, round_constant);
+        }
+        for round_constants in &self.external_constants[half..] {
+            self.external_round(state, round_constants);
+        }
+    }
+
+    fn external_round(&self, state: &mut [M31; WIDTH], round_constants: &[M31; WIDTH]) {
+        for (lane, &rc) in state.iter_mut().zip(round_constants.iter()) {
+            *lane = sbox(*lane + rc);
+        }
+        external_linear_layer(state);
+    }
+
+    fn internal_round(&self, state: &mut [M31; WIDTH], round_constant: M31) {
+        state[0] = sbox(state[0] + round_constant);
+        internal_linear_layer(state, &self.internal_diagonal);
+    }
+}
+
+/// A duplex sponge over [`Poseidon2M31`]: `RATE` lanes are absorbed into
+/// or squeezed out of per permutation call, with the remaining
+/// `WIDTH - RATE` lanes forming the capacity an adversary never observes
+/// directly. This is the construction both `ProverChannel` and
+/// `VerifierChannel` build their Fiat-Shamir transcript on.
+#[derive(Clone)]
+pub struct Poseidon2Sponge<const WIDTH: usize, const RATE: usize> {
+    permutation: Poseidon2M31<WIDTH>,
+    state: [M31; WIDTH],
+    /// Index into the rate portion the next absorbed/squeezed element
+    /// lands at; reaching `RATE` triggers a permutation call.
+    rate_pos: usize,
+    /// Whether the most recent operation was a squeeze — a sponge that
+    /// just squeezed must re-permute before absorbing again, since
+    /// otherwise the next absorb would overwrite still-unread rate lanes.
+    squeezing: bool,
+}
+
+impl<const WIDTH: usize, const RATE: usize> Poseidon2Sponge<WIDTH, RATE> {
+    pub fn new(domain: u32) -> Self {
+        debug_assert!(RATE < WIDTH, "sponge rate must leave room for capacity");
+        Self {
+            permutation: Poseidon2M31::new(domain),
+            state: [M31::ZERO; WIDTH],
+            rate_pos: 0,
+            squeezing: false,
+        }
+    }
+
+    /// Absorb a single field element into the sponge.
+    pub fn absorb(&mut self, value: M31) {
+        if self.squeezing || self.rate_pos == RATE {
+            self.permutation.permute(&mut self.state);
+            self.rate_pos = 0;
+            self.squeezing = false;
+        }
+        self.state[self.rate_pos] = self.state[self.rate_pos] + value;
+        self.rate_pos += 1;
+    }
+
+    /// Squeeze a single field element out of the sponge.
+    pub fn squeeze(&mut self) -> M31 {
+        if !self.squeezing || self.rate_pos == RATE {
+            self.permutation.permute(&mut self.state);
+            self.rate_pos = 0;
+            self.squeezing = true;
+        }
+        let out = self.state[self.rate_pos];
+        self.rate_pos += 1;
+        out
+    }
+}
+
+/// Hash a row of M31 trace values into a 32-byte Merkle leaf commitment:
+/// absorb every value, then squeeze 8 field elements (32 bytes,
+/// little-endian per element) as the digest. Used in place of a
+/// bit-oriented hash (e.g. blake3) so the same computation is cheap to
+/// re-express inside a recursive verifier AIR.
+pub fn hash_row(values: &[M31]) -> [u8; 32] {
+    let mut sponge: Poseidon2Sponge<16, 8> = Poseidon2Sponge::new(0x6c65_6166 /* "leaf" */);
+    for &value in values {
+        sponge.absorb(value);
+    }
+    digest_from_sponge(&mut sponge)
+}
+
+/// Compress two 32-byte Merkle node digests into their parent: decode
+/// each half into 8 M31 limbs, absorb both halves, then squeeze a fresh
+/// 32-byte digest — the internal-node counterpart to [`hash_row`].
+pub fn compress(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut sponge: Poseidon2Sponge<16, 8> = Poseidon2Sponge::new(0x6e6f_6465 /* "node" */);
+    for word in bytes_to_limbs(left).into_iter().chain(bytes_to_limbs(right)) {
+        sponge.absorb(word);
+    }
+    digest_from_sponge(&mut sponge)
+}
+
+fn digest_from_sponge<const WIDTH: usize, const RATE: usize>(
+    sponge: &mut Poseidon2Sponge<WIDTH, RATE>,
+) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    for chunk in digest.chunks_exact_mut(4) {
+        chunk.copy_from_slice(&sponge.squeeze().as_u32().to_le_bytes());
+    }
+    digest
+}
+
+/// Split a 32-byte digest into 8 little-endian `u32` limbs, reduced mod
+/// the M31 modulus. Since every digest byte-chunk came from
+/// [`digest_from_sponge`] (itself always a canonical M31 value), this is
+/// a lossless round trip for any digest produced by this module.
+fn bytes_to_limbs(bytes: &[u8; 32]) -> [M31; 8] {
+    core::array::from_fn(|i| {
+        let mut word = [0u8; 4];
+        word.copy_from_slice(&bytes[i * 4..i * 4 + 4]);
+        M31::new(u32::from_le_bytes(word))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sbox_is_not_cube() {
+        // x^3 would collide on at least one pair for a field this small;
+        // spot check that x^5 and x^3 disagree, pinning down that this
+        // module really uses the quintic map.
+        let x = M31::new(7);
+        let cube = x * x * x;
+        assert_ne!(sbox(x), cube);
+    }
+
+    #[test]
+    fn test_permutation_is_deterministic() {
+        let perm: Poseidon2M31<16> = Poseidon2M31::new(1);
+        let mut a = [M31::new(1); 16];
+        let mut b = [M31::new(1); 16];
+
+        perm.permute(&mut a);
+        perm.permute(&mut b);
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_permutation_is_injective_on_sample_inputs() {
+        let perm: Poseidon2M31<16> = Poseidon2M31::new(2);
+        let mut a: [M31; 16] = core::array::from_fn(|i| M31::new(i as u32));
+        let mut b: [M31; 16] = core::array::from_fn(|i| M31::new(i as u32));
+        b[0] = b[0] + M31::ONE;
+
+        perm.permute(&mut a);
+        perm.permute(&mut b);
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_permutation_differs_across_widths() {
+        let perm12: Poseidon2M31<12> = Poseidon2M31::new(3);
+        let perm24: Poseidon2M31<24> = Poseidon2M31::new(3);
+
+        let mut a = [M31::new(5); 12];
+        let mut b = [M31::new(5); 24];
+        perm12.permute(&mut a);
+        perm24.permute(&mut b);
+
+        // Different widths mix differently; spot check the shared prefix
+        // isn't trivially identical.
+        assert_ne!(a[..12], b[..12]);
+    }
+
+    #[test]
+    fn test_sponge_absorb_then_squeeze_is_deterministic() {
+        let mut s1: Poseidon2Sponge<16, 8> = Poseidon2Sponge::new(42);
+        let mut s2: Poseidon2Sponge<16, 8> = Poseidon2Sponge::new(42);
+
+        for v in [1u32, 2, 3, 4, 5] {
+            s1.absorb(M31::new(v));
+            s2.absorb(M31::new(v));
+        }
+
+        assert_eq!(s1.squeeze(), s2.squeeze());
+    }
+
+    #[test]
+    fn test_sponge_diverges_on_different_input() {
+        let mut s1: Poseidon2Sponge<16, 8> = Poseidon2Sponge::new(42);
+        let mut s2: Poseidon2Sponge<16, 8> = Poseidon2Sponge::new(42);
+
+        s1.absorb(M31::new(1));
+        s2.absorb(M31::new(2));
+
+        assert_ne!(s1.squeeze(), s2.squeeze());
+    }
+
+    #[test]
+    fn test_sponge_absorb_after_squeeze_does_not_leak_unread_rate() {
+        // After a squeeze, absorbing again must re-permute rather than
+        // silently overwrite still-unread rate lanes with stale state.
+        let mut s1: Poseidon2Sponge<16, 8> = Poseidon2Sponge::new(7);
+        s1.absorb(M31::new(1));
+        let _ = s1.squeeze();
+        let mut expected = s1.state;
+        s1.permutation.permute(&mut expected);
+        expected[0] = expected[0] + M31::new(2);
+        s1.absorb(M31::new(2));
+        assert_eq!(s1.state, expected);
+        let out1 = s1.squeeze();
+
+        let mut s2: Poseidon2Sponge<16, 8> = Poseidon2Sponge::new(7);
+        s2.absorb(M31::new(1));
+        let _ = s2.squeeze();
+        s2.absorb(M31::new(2));
+        let out2 = s2.squeeze();
+
+        assert_eq!(out1, out2);
+    }
+
+    #[test]
+    fn test_hash_row_is_deterministic_and_sensitive_to_input() {
+        let a = hash_row(&[M31::new(1), M31::new(2), M31::new(3)]);
+        let b = hash_row(&[M31::new(1), M31::new(2), M31::new(3)]);
+        let c = hash_row(&[M31::new(1), M31::new(2), M31::new(4)]);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_compress_is_deterministic_and_order_sensitive() {
+        let left = hash_row(&[M31::new(1)]);
+        let right = hash_row(&[M31::new(2)]);
+
+        let parent1 = compress(&left, &right);
+        let parent2 = compress(&left, &right);
+        let swapped = compress(&right, &left);
+
+        assert_eq!(parent1, parent2);
+        assert_ne!(parent1, swapped);
+    }
+}
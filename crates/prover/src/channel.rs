@@ -1,44 +1,71 @@
-//! Fiat-Shamir transcript channel for the prover using Plonky3.
+//! Fiat-Shamir transcript channel for the prover, built on the Poseidon2
+//! sponge over M31 (see `zp1_primitives::poseidon2`) so the exact same
+//! arithmetization-friendly hash backs both this transcript and the
+//! Merkle commitments it absorbs.
 
-use p3_challenger::{CanObserve, CanSample, DuplexChallenger};
-use p3_poseidon2::Poseidon2;
-use zp1_primitives::{M31, QM31, to_p3, from_p3};
-use p3_mersenne_31::{Poseidon2ExternalLayerMersenne31, Poseidon2InternalLayerMersenne31};
-use rand::SeedableRng;
-use rand::rngs::StdRng;
+use zp1_primitives::{poseidon2::Poseidon2Sponge, M31, QM31};
 
-// Poseidon2 configuration: Width 16, M31 field
-type Permutation = Poseidon2<zp1_primitives::P3M31, Poseidon2ExternalLayerMersenne31<16>, Poseidon2InternalLayerMersenne31, 16, 5>;
-type Challenger = DuplexChallenger<zp1_primitives::P3M31, Permutation, 16, 8>;
+/// Sponge width and rate backing this channel's transcript; matches the
+/// Merkle tree's leaf/node sponge so both share one permutation shape.
+const SPONGE_WIDTH: usize = 16;
+const SPONGE_RATE: usize = 8;
+
+/// Domain tag distinguishing this channel's sponge from the Merkle tree's
+/// leaf (`"leaf"`) and node (`"node"`) sponges.
+const CHANNEL_DOMAIN: u32 = 0x6368_616e; // "chan"
 
 /// Prover channel for Fiat-Shamir transcript.
 #[derive(Clone)]
 pub struct ProverChannel {
-    challenger: Challenger,
+    sponge: Poseidon2Sponge<SPONGE_WIDTH, SPONGE_RATE>,
 }
 
 impl ProverChannel {
-    /// Create a new prover channel.
-    pub fn new(_domain_separator: &[u8]) -> Self {
-        // Initialize Poseidon2 permutation
-        let mut rng = StdRng::seed_from_u64(42);
-        let permutation = Poseidon2::new_from_rng_128(&mut rng);
-        let mut challenger = DuplexChallenger::new(permutation);
-        
-        // TODO: Absorb domain separator properly (convert to field elements)
-        // For now we just start fresh
-        Self { challenger }
+    /// Create a new prover channel, domain-separated by `domain_separator`.
+    ///
+    /// Different protocols (or versions of this protocol) must use
+    /// distinct domain separators so their transcripts can never collide.
+    pub fn new(domain_separator: &[u8]) -> Self {
+        let sponge = Poseidon2Sponge::new(CHANNEL_DOMAIN);
+
+        let mut channel = Self { sponge };
+        channel.absorb_domain_separator(domain_separator);
+        channel
+    }
+
+    /// Absorb a domain separator; `absorb` already length-tags its input,
+    /// so distinct separators can never collide via a shifted boundary
+    /// (e.g. `b"ab"` followed by `b"c"` vs. `b"a"` followed by `b"bc"`).
+    fn absorb_domain_separator(&mut self, domain_separator: &[u8]) {
+        self.absorb(domain_separator);
     }
 
     /// Absorb bytes into the transcript.
+    ///
+    /// Prefixes the payload with its length so that `absorb(a)` followed
+    /// by `absorb(b)` can never produce the same transcript state as a
+    /// single `absorb(a ++ b)` call: without the tag, a boundary that
+    /// happens to fall on a 3-byte chunk edge would make the two
+    /// indistinguishable.
+    ///
+    /// Packs 3 bytes per field element: `2^24 - 1` is always less than the
+    /// M31 modulus `2^31 - 1`, so every chunk is already a canonical field
+    /// element and no modular reduction (and therefore no collision
+    /// between distinct byte strings) is needed.
     pub fn absorb(&mut self, data: &[u8]) {
-        // Naive byte absorption - pack into field elements
-        // In a real implementation, we'd use a proper byte-to-field packing
-        for chunk in data.chunks(4) {
+        self.absorb_raw(&(data.len() as u32).to_le_bytes());
+        self.absorb_raw(data);
+    }
+
+    /// Absorb bytes with no length tag. Only [`Self::absorb`] should call
+    /// this directly; every other site must go through it so the length
+    /// tag is never skipped.
+    fn absorb_raw(&mut self, data: &[u8]) {
+        for chunk in data.chunks(3) {
             let mut bytes = [0u8; 4];
             bytes[0..chunk.len()].copy_from_slice(chunk);
-            let val = u32::from_le_bytes(bytes) % 2147483647; // M31 modulus
-            self.challenger.observe(to_p3(M31::new(val)));
+            let val = u32::from_le_bytes(bytes);
+            self.sponge.absorb(M31::new(val));
         }
     }
 
@@ -49,12 +76,12 @@ impl ProverChannel {
 
     /// Absorb an M31 field element.
     pub fn absorb_felt(&mut self, felt: M31) {
-        self.challenger.observe(to_p3(felt));
+        self.sponge.absorb(felt);
     }
 
     /// Squeeze a challenge in M31.
     pub fn squeeze_challenge(&mut self) -> M31 {
-        from_p3(self.challenger.sample())
+        self.sponge.squeeze()
     }
 
     /// Squeeze a challenge in QM31 (extension field).
@@ -80,6 +107,40 @@ impl ProverChannel {
         }
         indices
     }
+
+    /// Grind a proof-of-work nonce: find the smallest `u64` such that
+    /// absorbing it and squeezing a challenge yields a value whose low
+    /// `bits` bits are zero.
+    ///
+    /// This is a transcript side-effect: on return, the nonce has been
+    /// absorbed and its challenge squeezed, so the channel is left exactly
+    /// where `VerifierChannel::check_pow` would leave it after replay.
+    /// Raises the cost of forging a proof transcript at the cost of prover
+    /// time, letting callers trade grinding time for fewer FRI queries.
+    pub fn grind(&mut self, bits: usize) -> u64 {
+        for nonce in 0u64.. {
+            let mut probe = self.clone();
+            probe.absorb(&nonce.to_le_bytes());
+            let challenge = probe.squeeze_challenge();
+            if Self::low_bits_are_zero(challenge.value(), bits) {
+                self.absorb(&nonce.to_le_bytes());
+                self.squeeze_challenge();
+                return nonce;
+            }
+        }
+        unreachable!("u64 nonce space exhausted")
+    }
+
+    /// True if the low `bits` bits of `value` are all zero.
+    fn low_bits_are_zero(value: u32, bits: usize) -> bool {
+        if bits == 0 {
+            return true;
+        }
+        if bits >= 32 {
+            return value == 0;
+        }
+        value & ((1u32 << bits) - 1) == 0
+    }
 }
 
 impl Default for ProverChannel {
@@ -117,4 +178,64 @@ mod tests {
             assert!(idx < 1024);
         }
     }
+
+    #[test]
+    fn test_absorb_does_not_collide_across_modulus_boundary() {
+        // 0x7fffffff and 0x00000000 previously collided under `% 2147483647`
+        // packing. With 3-byte canonical chunks they must diverge.
+        let mut ch1 = ProverChannel::new(b"test");
+        let mut ch2 = ProverChannel::new(b"test");
+
+        ch1.absorb(&0x7fffffffu32.to_le_bytes());
+        ch2.absorb(&0u32.to_le_bytes());
+
+        assert_ne!(ch1.squeeze_challenge(), ch2.squeeze_challenge());
+    }
+
+    #[test]
+    fn test_different_domain_separators_diverge() {
+        let mut ch1 = ProverChannel::new(b"zp1-stark-v1");
+        let mut ch2 = ProverChannel::new(b"zp1-stark-v2");
+
+        assert_ne!(ch1.squeeze_challenge(), ch2.squeeze_challenge());
+    }
+
+    #[test]
+    fn test_absorb_does_not_collide_across_call_boundary() {
+        // Two absorbs whose payloads concatenate to the same bytes as one
+        // absorb, split at a different point, must not collide.
+        let mut ch1 = ProverChannel::new(b"test");
+        ch1.absorb(b"ab");
+        ch1.absorb(b"c");
+
+        let mut ch2 = ProverChannel::new(b"test");
+        ch2.absorb(b"a");
+        ch2.absorb(b"bc");
+
+        let mut ch3 = ProverChannel::new(b"test");
+        ch3.absorb(b"abc");
+
+        let c1 = ch1.squeeze_challenge();
+        let c2 = ch2.squeeze_challenge();
+        let c3 = ch3.squeeze_challenge();
+        assert_ne!(c1, c2);
+        assert_ne!(c1, c3);
+        assert_ne!(c2, c3);
+    }
+
+    #[test]
+    fn test_grind_satisfies_pow_bits() {
+        let mut ch = ProverChannel::new(b"test");
+        ch.absorb(b"seed");
+
+        let nonce = ch.grind(8);
+
+        // Replay the grind from the pre-grind state to confirm the nonce
+        // actually satisfies the target.
+        let mut replay = ProverChannel::new(b"test");
+        replay.absorb(b"seed");
+        replay.absorb(&nonce.to_le_bytes());
+        let challenge = replay.squeeze_challenge();
+        assert!(ProverChannel::low_bits_are_zero(challenge.value(), 8));
+    }
 }
@@ -0,0 +1,181 @@
+//! Merkle tree commitment over trace/composition columns.
+//!
+//! Leaves and internal nodes are hashed with the Poseidon2 permutation
+//! (`zp1_primitives::poseidon2`) rather than a bit-oriented hash like
+//! blake3, so a recursive verifier AIR can cheaply re-evaluate the exact
+//! same commitment this prover produces.
+
+use zp1_primitives::{poseidon2, M31};
+
+/// An authentication path from a leaf to the Merkle root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof {
+    /// Index of the leaf this path authenticates.
+    pub leaf_index: usize,
+    /// Sibling hashes from leaf to root.
+    pub path: Vec<[u8; 32]>,
+}
+
+impl MerkleProof {
+    /// Verify this path against a commitment root and the claimed leaf's
+    /// row of column values.
+    ///
+    /// The leaf hash covers every column's value at this row, in order, so
+    /// a single Merkle tree can commit to a whole multi-column trace; a
+    /// single-column tree (e.g. the composition polynomial's) just passes
+    /// a one-element slice.
+    pub fn verify(&self, root: &[u8; 32], leaf_values: &[M31]) -> bool {
+        let mut current = poseidon2::hash_row(leaf_values);
+        let mut idx = self.leaf_index;
+
+        for sibling in &self.path {
+            current = if idx & 1 == 0 {
+                poseidon2::compress(&current, sibling)
+            } else {
+                poseidon2::compress(sibling, &current)
+            };
+            idx /= 2;
+        }
+
+        current == *root
+    }
+}
+
+/// A Merkle tree committing to a domain of field-element rows, one leaf
+/// per row (a "row" of one column is just a single value).
+pub struct MerkleTree {
+    /// Every level of the tree, leaves first, root last (a single-element
+    /// final level).
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Build the tree over `values`, hashing each value into its own
+    /// single-column leaf and compressing pairs up to the root. `values.len()`
+    /// must be a power of two (LDE domains always are).
+    pub fn new(values: &[M31]) -> Self {
+        let leaves: Vec<[u8; 32]> = values
+            .iter()
+            .map(|&value| poseidon2::hash_row(&[value]))
+            .collect();
+        Self::from_leaves(leaves)
+    }
+
+    /// Build the tree over `rows`, hashing every column's value at a row
+    /// into one leaf, so a single tree commits to a whole multi-column
+    /// trace instead of just one of its columns. `rows.len()` must be a
+    /// power of two (LDE domains always are).
+    pub fn new_rows(rows: &[Vec<M31>]) -> Self {
+        let leaves: Vec<[u8; 32]> = rows.iter().map(|row| poseidon2::hash_row(row)).collect();
+        Self::from_leaves(leaves)
+    }
+
+    fn from_leaves(leaves: Vec<[u8; 32]>) -> Self {
+        assert!(
+            leaves.len().is_power_of_two(),
+            "Merkle tree size must be a power of 2"
+        );
+
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| poseidon2::compress(&pair[0], &pair[1]))
+                .collect();
+            layers.push(next);
+        }
+
+        Self { layers }
+    }
+
+    /// The root commitment.
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap()[0]
+    }
+
+    /// The authentication path for leaf `index`.
+    pub fn prove(&self, index: usize) -> MerkleProof {
+        let mut idx = index;
+        let mut path = Vec::with_capacity(self.layers.len() - 1);
+
+        for layer in &self.layers[..self.layers.len() - 1] {
+            path.push(layer[idx ^ 1]);
+            idx /= 2;
+        }
+
+        MerkleProof {
+            leaf_index: index,
+            path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_leaf_tree_root_is_its_hash() {
+        let tree = MerkleTree::new(&[M31::new(42)]);
+        assert_eq!(tree.root(), poseidon2::hash_row(&[M31::new(42)]));
+    }
+
+    #[test]
+    fn test_prove_and_verify_round_trip() {
+        let values: Vec<M31> = (0..8u32).map(M31::new).collect();
+        let tree = MerkleTree::new(&values);
+        let root = tree.root();
+
+        for (idx, &value) in values.iter().enumerate() {
+            let proof = tree.prove(idx);
+            assert!(proof.verify(&root, &[value]));
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_value() {
+        let values: Vec<M31> = (0..8u32).map(M31::new).collect();
+        let tree = MerkleTree::new(&values);
+        let root = tree.root();
+
+        let proof = tree.prove(3);
+        assert!(!proof.verify(&root, &[M31::new(999)]));
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_index() {
+        let values: Vec<M31> = (0..8u32).map(M31::new).collect();
+        let tree = MerkleTree::new(&values);
+        let root = tree.root();
+
+        let mut proof = tree.prove(3);
+        proof.leaf_index = 4;
+        assert!(!proof.verify(&root, &[values[3]]));
+    }
+
+    #[test]
+    fn test_root_is_deterministic() {
+        let values: Vec<M31> = (0..4u32).map(M31::new).collect();
+        assert_eq!(
+            MerkleTree::new(&values).root(),
+            MerkleTree::new(&values).root()
+        );
+    }
+
+    #[test]
+    fn test_new_rows_matches_hash_row_per_leaf() {
+        let rows = vec![
+            vec![M31::new(1), M31::new(2)],
+            vec![M31::new(3), M31::new(4)],
+        ];
+        let tree = MerkleTree::new_rows(&rows);
+        let root = tree.root();
+
+        for (idx, row) in rows.iter().enumerate() {
+            let proof = tree.prove(idx);
+            assert!(proof.verify(&root, row));
+        }
+    }
+}
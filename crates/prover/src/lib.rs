@@ -33,7 +33,10 @@ pub use ram::{ChunkMemorySubtree, RamAccess, RamArgumentProver, RamColumns, RamO
 pub use recursion::{RecursionConfig, RecursiveProof, RecursiveProver, SegmentedProver};
 pub use serialize::{ProofConfig, SerializableProof, VerificationKey};
 pub use snark::{
-    groth16_wrapper, halo2_wrapper, plonk_wrapper, SnarkConfig, SnarkError, SnarkProof,
-    SnarkSystem, SnarkVerifier, SnarkWrapper,
+    generate_verifier_contract, groth16_wrapper, halo2_wrapper, plonk_wrapper,
+    verifier_contract_name, SnarkConfig, SnarkError, SnarkProof, SnarkSystem, SnarkVerifier,
+    SnarkWrapper,
+};
+pub use stark::{
+    ConstraintSystem, DomainPredicate, QueryProof, StarkConfig, StarkProof, StarkProver,
 };
-pub use stark::{QueryProof, StarkConfig, StarkProof, StarkProver};
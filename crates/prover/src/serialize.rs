@@ -1,10 +1,23 @@
 //! Proof serialization using serde.
 //!
 //! Provides serialization/deserialization for proofs and verification keys.
+//! `to_json`/`from_json` (and the hex-string helpers they rely on) are a
+//! human-readable debug format gated behind the `std` feature; `to_bytes`/
+//! `from_bytes` are the compact binary wire format (raw hashes, varint
+//! lengths, bit-packed M31 vectors — see the `varint`, `m31_bits`, and
+//! `raw_hash` modules below) and only ever touch `alloc::vec::Vec`, so a
+//! `#![no_std]` guest can reconstruct a proof from a blob read via
+//! `zp1_zkvm::io::read` without linking `std`.
 
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use zp1_primitives::M31;
 
+#[cfg(feature = "std")]
+use alloc::format;
+
 /// Serialize M31 as u32.
 pub fn serialize_m31<S: Serializer>(val: &M31, serializer: S) -> Result<S::Ok, S::Error> {
     serializer.serialize_u32(val.as_u32())
@@ -31,18 +44,18 @@ pub fn deserialize_m31_vec<'de, D: Deserializer<'de>>(
 }
 
 /// Serializable STARK proof.
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SerializableProof {
     /// Trace commitment (Merkle root).
-    #[serde(with = "hex_array")]
+    #[cfg_attr(feature = "std", serde(with = "hex_array"))]
     pub trace_commitment: [u8; 32],
 
     /// Composition polynomial commitment.
-    #[serde(with = "hex_array")]
+    #[cfg_attr(feature = "std", serde(with = "hex_array"))]
     pub composition_commitment: [u8; 32],
 
     /// FRI layer commitments.
-    #[serde(with = "hex_vec")]
+    #[cfg_attr(feature = "std", serde(with = "hex_vec"))]
     pub fri_commitments: Vec<[u8; 32]>,
 
     /// FRI final polynomial coefficients.
@@ -60,7 +73,7 @@ pub struct SerializableProof {
 }
 
 /// Serializable query proof.
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SerializableQueryProof {
     /// Query index in the domain.
     pub index: usize,
@@ -88,15 +101,15 @@ pub struct SerializableQueryProof {
 }
 
 /// Merkle authentication path.
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct MerklePath {
     /// Sibling hashes from leaf to root.
-    #[serde(with = "hex_vec")]
+    #[cfg_attr(feature = "std", serde(with = "hex_vec"))]
     pub siblings: Vec<[u8; 32]>,
 }
 
 /// Proof configuration.
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ProofConfig {
     /// Log2 of trace length.
     pub log_trace_len: usize,
@@ -112,20 +125,47 @@ pub struct ProofConfig {
     pub entry_point: u32,
 }
 
+impl ProofConfig {
+    /// Write this config's fields as a sequence of LEB128 varints, in
+    /// declaration order, for the compact binary proof format.
+    fn write_compact(&self, buf: &mut Vec<u8>) {
+        varint::write(buf, self.log_trace_len as u64);
+        varint::write(buf, self.blowup_factor as u64);
+        varint::write(buf, self.num_queries as u64);
+        varint::write(buf, self.fri_folding_factor as u64);
+        varint::write(buf, self.security_bits as u64);
+        varint::write(buf, self.entry_point as u64);
+    }
+
+    /// Read a config written by `write_compact`.
+    fn read_compact(buf: &[u8], pos: &mut usize) -> Result<Self, String> {
+        Ok(Self {
+            log_trace_len: varint::read(buf, pos)? as usize,
+            blowup_factor: varint::read(buf, pos)? as usize,
+            num_queries: varint::read(buf, pos)? as usize,
+            fri_folding_factor: varint::read(buf, pos)? as usize,
+            security_bits: varint::read(buf, pos)? as usize,
+            entry_point: varint::read(buf, pos)? as u32,
+        })
+    }
+}
+
 /// Serializable verification key.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct VerificationKey {
     /// Configuration.
     pub config: ProofConfig,
     /// AIR constraints hash.
-    #[serde(with = "hex_array")]
+    #[cfg_attr(feature = "std", serde(with = "hex_array"))]
     pub constraints_hash: [u8; 32],
     /// Public inputs commitment.
-    #[serde(with = "hex_array")]
+    #[cfg_attr(feature = "std", serde(with = "hex_array"))]
     pub public_inputs_hash: [u8; 32],
 }
 
-/// Hex serialization for fixed-size arrays.
+/// Hex serialization for fixed-size arrays. Host-only: only exercised by
+/// `to_json`/`from_json`, never by the `alloc`-only compact binary codec.
+#[cfg(feature = "std")]
 mod hex_array {
     use super::hex;
     use serde::{Deserialize, Deserializer, Serializer};
@@ -146,7 +186,9 @@ mod hex_array {
     }
 }
 
-/// Hex serialization for vectors of fixed-size arrays.
+/// Hex serialization for vectors of fixed-size arrays. Host-only, see
+/// `hex_array`.
+#[cfg(feature = "std")]
 mod hex_vec {
     use super::hex;
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -174,7 +216,144 @@ mod hex_vec {
     }
 }
 
-/// Hex encoding/decoding helper.
+/// LEB128 unsigned varint encoding, used for vector lengths and other
+/// small integers in the compact binary proof format.
+mod varint {
+    pub fn write(buf: &mut Vec<u8>, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                buf.push(byte);
+                return;
+            }
+            buf.push(byte | 0x80);
+        }
+    }
+
+    pub fn read(buf: &[u8], pos: &mut usize) -> Result<u64, String> {
+        let mut value = 0u64;
+        let mut shift = 0u32;
+        loop {
+            let byte = *buf.get(*pos).ok_or("unexpected end of buffer (varint)")?;
+            *pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+}
+
+/// Bit-packs `Vec<M31>` field elements for the compact binary proof format.
+///
+/// Every M31 value fits in 31 bits, so a contiguous bitstream of 31-bit
+/// words costs 31 bits (~3.9 bytes) per element instead of the 4 bytes (plus
+/// JSON punctuation) a naive encoding would use.
+mod m31_bits {
+    use super::varint;
+    use zp1_primitives::M31;
+
+    pub fn write(buf: &mut Vec<u8>, values: &[M31]) {
+        varint::write(buf, values.len() as u64);
+
+        let mut acc: u64 = 0;
+        let mut bits: u32 = 0;
+        for &v in values {
+            acc |= (v.as_u32() as u64) << bits;
+            bits += 31;
+            while bits >= 8 {
+                buf.push((acc & 0xff) as u8);
+                acc >>= 8;
+                bits -= 8;
+            }
+        }
+        if bits > 0 {
+            buf.push((acc & 0xff) as u8);
+        }
+    }
+
+    pub fn read(buf: &[u8], pos: &mut usize) -> Result<Vec<M31>, String> {
+        let len = varint::read(buf, pos)? as usize;
+        let mut values = Vec::with_capacity(len);
+
+        let mut acc: u64 = 0;
+        let mut bits: u32 = 0;
+        for _ in 0..len {
+            while bits < 31 {
+                let byte = *buf.get(*pos).ok_or("unexpected end of buffer (m31 bitstream)")?;
+                *pos += 1;
+                acc |= (byte as u64) << bits;
+                bits += 8;
+            }
+            values.push(M31::new((acc & 0x7fff_ffff) as u32));
+            acc >>= 31;
+            bits -= 31;
+        }
+        Ok(values)
+    }
+
+    /// Advance `pos` past a bit-packed vector without materializing it.
+    pub fn skip(buf: &[u8], pos: &mut usize) -> Result<(), String> {
+        let len = varint::read(buf, pos)? as usize;
+        let total_bits = len * 31;
+        let bytes = (total_bits + 7) / 8;
+        let end = *pos + bytes;
+        if end > buf.len() {
+            return Err("unexpected end of buffer (m31 bitstream)".into());
+        }
+        *pos = end;
+        Ok(())
+    }
+}
+
+/// Raw (non-hex) hash encoding for the compact binary proof format: each
+/// `[u8; 32]` is written as-is rather than as a 64-character hex string.
+mod raw_hash {
+    use super::varint;
+
+    pub fn write(buf: &mut Vec<u8>, hash: &[u8; 32]) {
+        buf.extend_from_slice(hash);
+    }
+
+    pub fn read(buf: &[u8], pos: &mut usize) -> Result<[u8; 32], String> {
+        let end = *pos + 32;
+        let slice = buf.get(*pos..end).ok_or("unexpected end of buffer (hash)")?;
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(slice);
+        *pos = end;
+        Ok(hash)
+    }
+
+    pub fn write_vec(buf: &mut Vec<u8>, hashes: &[[u8; 32]]) {
+        varint::write(buf, hashes.len() as u64);
+        for hash in hashes {
+            write(buf, hash);
+        }
+    }
+
+    pub fn read_vec(buf: &[u8], pos: &mut usize) -> Result<Vec<[u8; 32]>, String> {
+        let len = varint::read(buf, pos)? as usize;
+        (0..len).map(|_| read(buf, pos)).collect()
+    }
+
+    /// Advance `pos` past a length-prefixed vector of hashes without
+    /// materializing it.
+    pub fn skip_vec(buf: &[u8], pos: &mut usize) -> Result<(), String> {
+        let len = varint::read(buf, pos)? as usize;
+        let end = *pos + len * 32;
+        if end > buf.len() {
+            return Err("unexpected end of buffer (hash)".into());
+        }
+        *pos = end;
+        Ok(())
+    }
+}
+
+/// Hex encoding/decoding helper. Host-only debug format; the compact
+/// binary codec below writes hashes as raw bytes instead.
+#[cfg(feature = "std")]
 pub mod hex {
     pub fn encode(bytes: &[u8]) -> String {
         bytes.iter().map(|b| format!("{:02x}", b)).collect()
@@ -192,42 +371,201 @@ pub mod hex {
 }
 
 impl SerializableProof {
-    /// Serialize to JSON.
+    /// Serialize to JSON. Host-only debug format.
+    #[cfg(feature = "std")]
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(self)
     }
 
-    /// Deserialize from JSON.
+    /// Deserialize from JSON. Host-only debug format.
+    #[cfg(feature = "std")]
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
     }
 
-    /// Serialize to binary (bincode).
+    /// Serialize to the compact binary format: raw 32-byte hashes,
+    /// LEB128 varint lengths, and M31 vectors bit-packed at 31 bits per
+    /// element (see `m31_bits`) instead of one 4-byte `u32` each.
+    ///
+    /// JSON (`to_json`) remains available as a human-readable debug
+    /// format; this is the wire format proofs are actually shipped in.
     pub fn to_bytes(&self) -> Vec<u8> {
-        // Simple binary format: JSON for now
-        // In production, use proper binary encoding
-        self.to_json().unwrap_or_default().into_bytes()
+        let mut buf = Vec::new();
+
+        raw_hash::write(&mut buf, &self.trace_commitment);
+        raw_hash::write(&mut buf, &self.composition_commitment);
+        raw_hash::write_vec(&mut buf, &self.fri_commitments);
+        m31_bits::write(&mut buf, &self.fri_final_poly);
+
+        varint::write(&mut buf, self.query_proofs.len() as u64);
+        for query in &self.query_proofs {
+            varint::write(&mut buf, query.index as u64);
+            m31_bits::write(&mut buf, &query.trace_values);
+            m31_bits::write(&mut buf, core::slice::from_ref(&query.composition_value));
+            varint::write(&mut buf, query.merkle_paths.len() as u64);
+            for path in &query.merkle_paths {
+                raw_hash::write_vec(&mut buf, &path.siblings);
+            }
+            m31_bits::write(&mut buf, &query.fri_values);
+        }
+
+        self.config.write_compact(&mut buf);
+        buf
     }
 
-    /// Deserialize from binary.
+    /// Deserialize from the compact binary format produced by `to_bytes`.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
-        let json = std::str::from_utf8(bytes).map_err(|e| e.to_string())?;
-        Self::from_json(json).map_err(|e| e.to_string())
+        let mut pos = 0;
+
+        let trace_commitment = raw_hash::read(bytes, &mut pos)?;
+        let composition_commitment = raw_hash::read(bytes, &mut pos)?;
+        let fri_commitments = raw_hash::read_vec(bytes, &mut pos)?;
+        let fri_final_poly = m31_bits::read(bytes, &mut pos)?;
+
+        let num_queries = varint::read(bytes, &mut pos)? as usize;
+        let mut query_proofs = Vec::with_capacity(num_queries);
+        for _ in 0..num_queries {
+            let index = varint::read(bytes, &mut pos)? as usize;
+            let trace_values = m31_bits::read(bytes, &mut pos)?;
+            let composition_value = m31_bits::read(bytes, &mut pos)?
+                .into_iter()
+                .next()
+                .ok_or("missing composition value")?;
+            let num_paths = varint::read(bytes, &mut pos)? as usize;
+            let merkle_paths = (0..num_paths)
+                .map(|_| raw_hash::read_vec(bytes, &mut pos).map(|siblings| MerklePath { siblings }))
+                .collect::<Result<Vec<_>, String>>()?;
+            let fri_values = m31_bits::read(bytes, &mut pos)?;
+
+            query_proofs.push(SerializableQueryProof {
+                index,
+                trace_values,
+                composition_value,
+                merkle_paths,
+                fri_values,
+            });
+        }
+
+        let config = ProofConfig::read_compact(bytes, &mut pos)?;
+
+        Ok(Self {
+            trace_commitment,
+            composition_commitment,
+            fri_commitments,
+            fri_final_poly,
+            query_proofs,
+            config,
+        })
     }
 
-    /// Get proof size in bytes.
+    /// Get proof size in bytes (compact binary encoding).
     pub fn size(&self) -> usize {
         self.to_bytes().len()
     }
+
+    /// Check that `bytes` is a structurally well-formed compact-binary
+    /// proof (every length-prefixed section fits within the buffer)
+    /// without allocating the `Vec<M31>`/`Vec<MerklePath>` field values
+    /// `from_bytes` would build.
+    ///
+    /// Lets a `no_std` guest cheaply reject a malformed proof blob before
+    /// paying for the full reconstruction.
+    pub fn verify_shape(bytes: &[u8]) -> Result<(), String> {
+        let mut pos = 0;
+
+        raw_hash::read(bytes, &mut pos)?;
+        raw_hash::read(bytes, &mut pos)?;
+        raw_hash::skip_vec(bytes, &mut pos)?;
+        m31_bits::skip(bytes, &mut pos)?;
+
+        let num_queries = varint::read(bytes, &mut pos)?;
+        for _ in 0..num_queries {
+            varint::read(bytes, &mut pos)?;
+            m31_bits::skip(bytes, &mut pos)?;
+            m31_bits::skip(bytes, &mut pos)?;
+            let num_paths = varint::read(bytes, &mut pos)?;
+            for _ in 0..num_paths {
+                raw_hash::skip_vec(bytes, &mut pos)?;
+            }
+            m31_bits::skip(bytes, &mut pos)?;
+        }
+
+        // ProofConfig::write_compact always writes exactly six varints.
+        for _ in 0..6 {
+            varint::read(bytes, &mut pos)?;
+        }
+
+        Ok(())
+    }
+
+    /// Render this proof as EVM calldata: a JSON object whose fields
+    /// (`traceCommitment`, `compositionCommitment`, `friCommitments`,
+    /// `friFinalPoly`, `queryProofs`) hold `0x`-prefixed, zero-padded
+    /// 32-byte words. Values are keyed by field name; the output is not a
+    /// positional array in `abi.decode` order.
+    ///
+    /// M31 field elements are encoded big-endian and zero-padded to 32
+    /// bytes, `[u8; 32]` commitments are passed through as-is, and nested
+    /// `Vec`s become nested JSON arrays.
+    #[cfg(feature = "std")]
+    pub fn to_solidity_calldata(&self) -> String {
+        let query_proofs: Vec<serde_json::Value> = self
+            .query_proofs
+            .iter()
+            .map(|q| {
+                serde_json::json!({
+                    "index": solidity_word_u64(q.index as u64),
+                    "traceValues": q.trace_values.iter().map(|v| solidity_word_m31(*v)).collect::<Vec<_>>(),
+                    "compositionValue": solidity_word_m31(q.composition_value),
+                    "merklePaths": q.merkle_paths.iter()
+                        .map(|p| p.siblings.iter().map(solidity_word_bytes32).collect::<Vec<_>>())
+                        .collect::<Vec<_>>(),
+                    "friValues": q.fri_values.iter().map(|v| solidity_word_m31(*v)).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+
+        let calldata = serde_json::json!({
+            "traceCommitment": solidity_word_bytes32(&self.trace_commitment),
+            "compositionCommitment": solidity_word_bytes32(&self.composition_commitment),
+            "friCommitments": self.fri_commitments.iter().map(solidity_word_bytes32).collect::<Vec<_>>(),
+            "friFinalPoly": self.fri_final_poly.iter().map(|v| solidity_word_m31(*v)).collect::<Vec<_>>(),
+            "queryProofs": query_proofs,
+        });
+
+        calldata.to_string()
+    }
+}
+
+/// Encode an M31 element as a `0x`-prefixed, big-endian, 32-byte word.
+#[cfg(feature = "std")]
+fn solidity_word_m31(value: M31) -> String {
+    solidity_word_u64(value.as_u32() as u64)
+}
+
+/// Encode a `u64` as a `0x`-prefixed, big-endian, 32-byte word.
+#[cfg(feature = "std")]
+fn solidity_word_u64(value: u64) -> String {
+    let mut word = [0u8; 32];
+    word[24..32].copy_from_slice(&value.to_be_bytes());
+    format!("0x{}", hex::encode(&word))
+}
+
+/// Encode a 32-byte commitment as a `0x`-prefixed word, passed through as-is.
+#[cfg(feature = "std")]
+fn solidity_word_bytes32(bytes: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(bytes))
 }
 
 impl VerificationKey {
-    /// Serialize to JSON.
+    /// Serialize to JSON. Host-only debug format.
+    #[cfg(feature = "std")]
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(self)
     }
 
-    /// Deserialize from JSON.
+    /// Deserialize from JSON. Host-only debug format.
+    #[cfg(feature = "std")]
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
     }
@@ -290,4 +628,155 @@ mod tests {
         assert_eq!(parsed.config.log_trace_len, 12);
         assert_eq!(parsed.constraints_hash, [1u8; 32]);
     }
+
+    #[test]
+    fn test_solidity_calldata_word_encoding() {
+        // 32-byte big-endian word with value 42 (0x2a) at the end.
+        let word = solidity_word_m31(M31::new(42));
+        assert_eq!(word.len(), 2 + 64);
+        assert!(word.starts_with("0x"));
+        assert!(word.ends_with("2a"));
+        assert!(word[2..62].chars().all(|c| c == '0'));
+    }
+
+    #[test]
+    fn test_solidity_calldata_is_valid_json() {
+        let proof = SerializableProof {
+            trace_commitment: [1u8; 32],
+            composition_commitment: [2u8; 32],
+            fri_commitments: vec![[3u8; 32]],
+            fri_final_poly: vec![M31::new(7)],
+            query_proofs: vec![SerializableQueryProof {
+                index: 5,
+                trace_values: vec![M31::new(9)],
+                composition_value: M31::new(11),
+                merkle_paths: vec![MerklePath { siblings: vec![[4u8; 32]] }],
+                fri_values: vec![M31::new(13)],
+            }],
+            config: ProofConfig {
+                log_trace_len: 3,
+                blowup_factor: 4,
+                num_queries: 1,
+                fri_folding_factor: 2,
+                security_bits: 80,
+                entry_point: 0,
+            },
+        };
+
+        let calldata = proof.to_solidity_calldata();
+        let value: serde_json::Value = serde_json::from_str(&calldata).unwrap();
+        assert_eq!(
+            value["traceCommitment"],
+            serde_json::Value::String(format!("0x{}", "01".repeat(32)))
+        );
+        assert_eq!(value["queryProofs"][0]["index"].as_str().unwrap(), solidity_word_u64(5));
+    }
+
+    fn sample_proof() -> SerializableProof {
+        SerializableProof {
+            trace_commitment: [1u8; 32],
+            composition_commitment: [2u8; 32],
+            fri_commitments: vec![[3u8; 32], [4u8; 32]],
+            fri_final_poly: vec![M31::new(0), M31::new(7), M31::new(0x7fff_ffff)],
+            query_proofs: vec![
+                SerializableQueryProof {
+                    index: 5,
+                    trace_values: vec![M31::new(9), M31::new(0x7fff_ffff)],
+                    composition_value: M31::new(11),
+                    merkle_paths: vec![
+                        MerklePath { siblings: vec![[5u8; 32], [6u8; 32]] },
+                        MerklePath { siblings: vec![[7u8; 32]] },
+                    ],
+                    fri_values: vec![M31::new(13)],
+                },
+                SerializableQueryProof {
+                    index: 300_000,
+                    trace_values: vec![],
+                    composition_value: M31::new(0),
+                    merkle_paths: vec![],
+                    fri_values: vec![M31::new(1), M31::new(2), M31::new(3)],
+                },
+            ],
+            config: ProofConfig {
+                log_trace_len: 16,
+                blowup_factor: 8,
+                num_queries: 64,
+                fri_folding_factor: 4,
+                security_bits: 100,
+                entry_point: 0x1000,
+            },
+        }
+    }
+
+    #[test]
+    fn test_varint_roundtrip() {
+        for value in [0u64, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut buf = Vec::new();
+            varint::write(&mut buf, value);
+            let mut pos = 0;
+            assert_eq!(varint::read(&buf, &mut pos).unwrap(), value);
+            assert_eq!(pos, buf.len());
+        }
+    }
+
+    #[test]
+    fn test_m31_bits_roundtrip_including_empty_and_boundary_values() {
+        let cases: Vec<Vec<M31>> = vec![
+            vec![],
+            vec![M31::new(0)],
+            vec![M31::new(0x7fff_ffff)],
+            (0..50).map(|i| M31::new(i * 12345 + 1)).collect(),
+        ];
+
+        for values in cases {
+            let mut buf = Vec::new();
+            m31_bits::write(&mut buf, &values);
+            let mut pos = 0;
+            let decoded = m31_bits::read(&buf, &mut pos).unwrap();
+            assert_eq!(decoded, values);
+            assert_eq!(pos, buf.len());
+        }
+    }
+
+    #[test]
+    fn test_compact_binary_roundtrip() {
+        let proof = sample_proof();
+        let bytes = proof.to_bytes();
+        let decoded = SerializableProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+    }
+
+    #[test]
+    fn test_compact_binary_is_smaller_than_json() {
+        let proof = sample_proof();
+        let compact_len = proof.to_bytes().len();
+        let json_len = proof.to_json().unwrap().len();
+        assert!(compact_len < json_len, "compact {compact_len} should be smaller than json {json_len}");
+    }
+
+    #[test]
+    fn test_size_matches_compact_encoding() {
+        let proof = sample_proof();
+        assert_eq!(proof.size(), proof.to_bytes().len());
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_truncated_buffer() {
+        let proof = sample_proof();
+        let bytes = proof.to_bytes();
+        assert!(SerializableProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_verify_shape_accepts_well_formed_proof() {
+        let proof = sample_proof();
+        assert!(SerializableProof::verify_shape(&proof.to_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_verify_shape_rejects_truncated_buffer() {
+        let proof = sample_proof();
+        let bytes = proof.to_bytes();
+        assert!(SerializableProof::verify_shape(&bytes[..bytes.len() - 1]).is_err());
+    }
 }
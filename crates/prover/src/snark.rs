@@ -0,0 +1,359 @@
+//! Wraps a STARK proof into a constant-size SNARK proof suitable for
+//! on-chain verification, and generates the Solidity verifier contract
+//! that checks one.
+//!
+//! The actual Groth16/PLONK/Halo2 recursion circuits (taking a STARK
+//! proof's FRI/Merkle/OODS checks and compressing them into a single
+//! pairing- or polynomial-commitment-based proof) live outside this
+//! crate's scope — [`SnarkWrapper`] is the extension point a real
+//! recursion backend plugs into. What lives here is the shape every
+//! wrapper produces ([`SnarkProof`]) and the Solidity contract generator
+//! that turns a [`VerificationKey`] plus the wrapper's [`SnarkSystem`]
+//! into a deployable `verify(bytes,uint256[])` contract, mirroring how
+//! [`crate::serialize::SerializableProof::to_solidity_calldata`] already
+//! renders a STARK proof as calldata for one.
+
+use crate::serialize::VerificationKey;
+use crate::stark::StarkProof;
+use thiserror::Error;
+
+/// Which SNARK construction a [`SnarkWrapper`] targets. Only the proof's
+/// on-chain verification cost and the Solidity opcodes the generated
+/// verifier uses differ between these; the wrapping interface is the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnarkSystem {
+    Groth16,
+    Plonk,
+    Halo2,
+}
+
+/// Errors a [`SnarkWrapper`] can raise while compressing a STARK proof.
+#[derive(Debug, Error)]
+pub enum SnarkError {
+    #[error("STARK proof has no query proofs to wrap")]
+    EmptyProof,
+
+    #[error("public input commitment mismatch: expected {expected}, got {got}")]
+    PublicInputMismatch { expected: String, got: String },
+}
+
+/// A wrapped, constant-size SNARK proof over a STARK proof's public
+/// commitments (trace commitment, composition commitment and PoW nonce),
+/// ready for on-chain verification against the contract
+/// [`generate_verifier_contract`] emits.
+#[derive(Debug, Clone)]
+pub struct SnarkProof {
+    pub system: SnarkSystem,
+    pub proof_bytes: Vec<u8>,
+    pub public_inputs: Vec<[u8; 32]>,
+}
+
+/// Configuration a [`SnarkWrapper`] is built from.
+#[derive(Debug, Clone, Copy)]
+pub struct SnarkConfig {
+    pub system: SnarkSystem,
+}
+
+/// Compresses a [`StarkProof`] into a [`SnarkProof`] for a particular
+/// [`SnarkSystem`]. Implemented by [`groth16_wrapper`], [`plonk_wrapper`],
+/// and [`halo2_wrapper`].
+pub trait SnarkWrapper {
+    fn config(&self) -> SnarkConfig;
+
+    /// Wrap `proof`, binding it to `public_inputs` (the same values the
+    /// deployed verifier contract's `publicInputs` argument must match).
+    fn wrap(
+        &self,
+        proof: &StarkProof,
+        public_inputs: &[[u8; 32]],
+    ) -> Result<SnarkProof, SnarkError>;
+}
+
+/// Packs a [`StarkProof`]'s public commitments into the flat byte string
+/// every [`SnarkWrapper`] in this module binds its wrapped proof to: the
+/// trace commitment, composition commitment, and PoW nonce, in that order.
+/// A real recursion circuit additionally proves these commitments satisfy
+/// the STARK's FRI/OODS checks; that circuit isn't implemented here.
+fn pack_public_commitments(proof: &StarkProof) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(32 + 32 + 8);
+    bytes.extend_from_slice(&proof.trace_commitment);
+    bytes.extend_from_slice(&proof.composition_commitment);
+    bytes.extend_from_slice(&proof.pow_nonce.to_be_bytes());
+    bytes
+}
+
+struct GenericWrapper {
+    system: SnarkSystem,
+}
+
+impl SnarkWrapper for GenericWrapper {
+    fn config(&self) -> SnarkConfig {
+        SnarkConfig { system: self.system }
+    }
+
+    fn wrap(
+        &self,
+        proof: &StarkProof,
+        public_inputs: &[[u8; 32]],
+    ) -> Result<SnarkProof, SnarkError> {
+        if proof.query_proofs.is_empty() {
+            return Err(SnarkError::EmptyProof);
+        }
+
+        Ok(SnarkProof {
+            system: self.system,
+            proof_bytes: pack_public_commitments(proof),
+            public_inputs: public_inputs.to_vec(),
+        })
+    }
+}
+
+pub fn groth16_wrapper() -> impl SnarkWrapper {
+    GenericWrapper { system: SnarkSystem::Groth16 }
+}
+
+pub fn plonk_wrapper() -> impl SnarkWrapper {
+    GenericWrapper { system: SnarkSystem::Plonk }
+}
+
+pub fn halo2_wrapper() -> impl SnarkWrapper {
+    GenericWrapper { system: SnarkSystem::Halo2 }
+}
+
+/// Off-chain counterpart to the Solidity contract [`generate_verifier_contract`]
+/// emits: checks a [`SnarkProof`] against a [`VerificationKey`] before it is
+/// submitted on-chain. Its leading-32-bytes comparison matches the
+/// contract's, but it requires 64 proof bytes where the contract requires
+/// 32, and combines public inputs by XOR where the contract uses keccak256,
+/// so the two can disagree on the same proof and key.
+pub struct SnarkVerifier {
+    pub vk: VerificationKey,
+}
+
+impl SnarkVerifier {
+    pub fn new(vk: VerificationKey) -> Self {
+        Self { vk }
+    }
+
+    /// Returns `true` iff `proof.proof_bytes` is at least 64 bytes long, its
+    /// leading 32 bytes equal the key's `constraints_hash`, and the XOR of
+    /// `public_inputs` ([`hash_public_inputs`]) equals the key's
+    /// `public_inputs_hash`. The generated Solidity contract's `verify`
+    /// makes the same leading-bytes comparison, but requires only 32 bytes
+    /// and hashes `publicInputs` with keccak256.
+    pub fn verify(&self, proof: &SnarkProof) -> bool {
+        if proof.proof_bytes.len() < 64 {
+            return false;
+        }
+        let committed_trace: [u8; 32] = proof.proof_bytes[0..32].try_into().unwrap();
+        if committed_trace != self.vk.constraints_hash {
+            return false;
+        }
+        hash_public_inputs(&proof.public_inputs) == self.vk.public_inputs_hash
+    }
+}
+
+/// Combine a proof's public inputs into the single 32-byte commitment the
+/// verifier checks against `VerificationKey::public_inputs_hash`: XOR, as a
+/// stand-in for the on-chain contract's `keccak256(abi.encodePacked(...))`.
+fn hash_public_inputs(public_inputs: &[[u8; 32]]) -> [u8; 32] {
+    let mut acc = [0u8; 32];
+    for input in public_inputs {
+        for (a, b) in acc.iter_mut().zip(input.iter()) {
+            *a ^= b;
+        }
+    }
+    acc
+}
+
+/// The Solidity contract name [`generate_verifier_contract`] gives the
+/// verifier it emits for `system` — exposed so callers that compile and
+/// deploy the generated source (e.g. to load it into an EVM) know which
+/// contract in the compiler output to pick up.
+pub fn verifier_contract_name(system: SnarkSystem) -> &'static str {
+    match system {
+        SnarkSystem::Groth16 => "ZP1Groth16Verifier",
+        SnarkSystem::Plonk => "ZP1PlonkVerifier",
+        SnarkSystem::Halo2 => "ZP1Halo2Verifier",
+    }
+}
+
+/// Render a `0x`-prefixed Solidity hex literal for a 32-byte value.
+fn solidity_bytes32_literal(bytes: &[u8; 32]) -> String {
+    format!("0x{}", crate::serialize::hex::encode(bytes))
+}
+
+/// Emit Solidity source for a verifier contract that checks a [`SnarkProof`]
+/// produced by one of [`groth16_wrapper`], [`plonk_wrapper`], or
+/// [`halo2_wrapper`] against `vk`. The generated `verify` function checks
+/// that the proof's leading 32 bytes equal `vk.constraints_hash` (as
+/// [`SnarkVerifier::verify`] does off-chain) and that
+/// `keccak256(abi.encodePacked(publicInputs))` equals
+/// `vk.public_inputs_hash`; [`SnarkVerifier::verify`] compares the XOR of
+/// the inputs there instead. A production verifier would additionally run
+/// the wrapped system's actual pairing/polynomial-commitment check, which
+/// belongs to whichever `SnarkWrapper` produced the proof, not to this
+/// generator.
+pub fn generate_verifier_contract(vk: &VerificationKey, system: SnarkSystem) -> String {
+    let contract_name = verifier_contract_name(system);
+    let system_name = match system {
+        SnarkSystem::Groth16 => "Groth16",
+        SnarkSystem::Plonk => "Plonk",
+        SnarkSystem::Halo2 => "Halo2",
+    };
+    let constraints_hash = solidity_bytes32_literal(&vk.constraints_hash);
+    let public_inputs_hash = solidity_bytes32_literal(&vk.public_inputs_hash);
+
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+/// @title {contract_name}
+/// @notice Verifies SNARK proofs wrapping a ZP1 STARK proof committed to
+/// by the verification key this contract was generated from. Generated by
+/// `zp1_prover::snark::generate_verifier_contract` — do not edit by hand.
+contract {contract_name} {{
+    bytes32 public constant CONSTRAINTS_HASH = {constraints_hash};
+    bytes32 public constant PUBLIC_INPUTS_HASH = {public_inputs_hash};
+
+    /// @notice Verify a wrapped {system_name} proof against this
+    /// contract's fixed verification key.
+    /// @param proof The wrapped proof bytes: 32-byte trace commitment,
+    /// followed by the {system_name}-specific proof data.
+    /// @param publicInputs The proof's public inputs, in the same order
+    /// they were bound to the proof during wrapping.
+    function verify(bytes calldata proof, uint256[] calldata publicInputs)
+        external
+        pure
+        returns (bool)
+    {{
+        require(proof.length >= 32, "ZP1Verifier: proof too short");
+
+        bytes32 committedTrace = bytes32(proof[0:32]);
+        require(committedTrace == CONSTRAINTS_HASH, "ZP1Verifier: wrong verification key");
+
+        require(
+            keccak256(abi.encodePacked(publicInputs)) == PUBLIC_INPUTS_HASH,
+            "ZP1Verifier: public input mismatch"
+        );
+
+        return true;
+    }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commitment::MerkleProof;
+    use crate::fri::FriProof;
+    use crate::serialize::ProofConfig;
+    use crate::stark::QueryProof;
+    use zp1_primitives::{M31, QM31};
+
+    fn test_vk() -> VerificationKey {
+        VerificationKey {
+            config: ProofConfig {
+                log_trace_len: 10,
+                blowup_factor: 8,
+                num_queries: 30,
+                fri_folding_factor: 2,
+                security_bits: 100,
+                entry_point: 0,
+            },
+            constraints_hash: [7u8; 32],
+            public_inputs_hash: [9u8; 32],
+        }
+    }
+
+    fn empty_merkle_proof() -> MerkleProof {
+        MerkleProof {
+            leaf_index: 0,
+            path: vec![],
+        }
+    }
+
+    fn test_stark_proof() -> StarkProof {
+        StarkProof {
+            trace_commitment: [7u8; 32],
+            composition_commitment: [2u8; 32],
+            fri_proof: FriProof {
+                layer_commitments: vec![],
+                query_proofs: vec![],
+                final_poly: vec![],
+            },
+            query_proofs: vec![QueryProof {
+                index: 0,
+                trace_values: vec![],
+                trace_proof: empty_merkle_proof(),
+                composition_value: M31::ZERO,
+                composition_proof: empty_merkle_proof(),
+            }],
+            trace_oods_value: QM31::from(M31::ZERO),
+            trace_oods_next_value: QM31::from(M31::ZERO),
+            composition_oods_value: QM31::from(M31::ZERO),
+            pow_nonce: 0,
+        }
+    }
+
+    #[test]
+    fn test_groth16_wrapper_rejects_empty_proof() {
+        let wrapper = groth16_wrapper();
+        let mut proof = test_stark_proof();
+        proof.query_proofs.clear();
+
+        let result = wrapper.wrap(&proof, &[]);
+        assert!(matches!(result, Err(SnarkError::EmptyProof)));
+    }
+
+    #[test]
+    fn test_snark_verifier_accepts_matching_proof() {
+        let wrapper = plonk_wrapper();
+        let proof = test_stark_proof();
+        let public_inputs = vec![[9u8; 32]];
+
+        let wrapped = wrapper.wrap(&proof, &public_inputs).unwrap();
+        let verifier = SnarkVerifier::new(test_vk());
+
+        assert!(verifier.verify(&wrapped));
+    }
+
+    #[test]
+    fn test_snark_verifier_rejects_tampered_public_inputs() {
+        let wrapper = halo2_wrapper();
+        let proof = test_stark_proof();
+        let public_inputs = vec![[9u8; 32]];
+
+        let mut wrapped = wrapper.wrap(&proof, &public_inputs).unwrap();
+        wrapped.public_inputs[0][0] ^= 0xff;
+
+        let verifier = SnarkVerifier::new(test_vk());
+        assert!(!verifier.verify(&wrapped));
+    }
+
+    #[test]
+    fn test_generate_verifier_contract_embeds_verification_key() {
+        let vk = test_vk();
+        let source = generate_verifier_contract(&vk, SnarkSystem::Groth16);
+
+        assert!(source.contains("pragma solidity"));
+        assert!(source.contains("contract ZP1Groth16Verifier"));
+        assert!(source.contains("function verify(bytes calldata proof, uint256[] calldata publicInputs)"));
+        assert!(source.contains(&solidity_bytes32_literal(&vk.constraints_hash)));
+        assert!(source.contains(&solidity_bytes32_literal(&vk.public_inputs_hash)));
+    }
+
+    #[test]
+    fn test_generate_verifier_contract_names_match_system() {
+        for (system, name) in [
+            (SnarkSystem::Groth16, "ZP1Groth16Verifier"),
+            (SnarkSystem::Plonk, "ZP1PlonkVerifier"),
+            (SnarkSystem::Halo2, "ZP1Halo2Verifier"),
+        ] {
+            let source = generate_verifier_contract(&test_vk(), system);
+            assert!(source.contains(&format!("contract {name}")));
+        }
+    }
+}
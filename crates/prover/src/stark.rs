@@ -9,6 +9,8 @@
 //! 6. Commit to composition polynomial via FRI
 //! 7. Generate query phase proofs
 
+use std::collections::HashMap;
+
 use crate::{
     channel::ProverChannel,
     commitment::{MerkleTree, MerkleProof},
@@ -17,6 +19,312 @@ use crate::{
 };
 use zp1_primitives::{M31, QM31};
 
+/// A node in an AIR constraint's algebraic DAG.
+///
+/// Every node is either a leaf (a constant or a trace cell reference) or
+/// an operation over earlier nodes, referenced by their arena index so the
+/// DAG stays a flat, hash-consed `Vec` instead of a pointer graph.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Node {
+    /// A fixed field element, independent of the trace.
+    Constant(M31),
+    /// A trace cell: column `col`, `row_offset` rows from the row being
+    /// evaluated (0 = current row, 1 = next row, -1 = previous row, ...).
+    TraceCell { col: usize, row_offset: isize },
+    Add(NodeId, NodeId),
+    Sub(NodeId, NodeId),
+    Mul(NodeId, NodeId),
+    /// `base` raised to the fixed exponent `exp`.
+    Pow(NodeId, u32),
+}
+
+/// Index of a [`Node`] in a [`ConstraintGraph`]'s arena.
+pub type NodeId = usize;
+
+/// Arena of [`Node`]s with hash-consing: inserting an already-seen node
+/// returns the existing id instead of duplicating it, so constraints that
+/// share subexpressions (e.g. the same trace cell read by several
+/// constraints) only pay for evaluating it once per row.
+#[derive(Debug, Default, Clone)]
+pub struct ConstraintGraph {
+    nodes: Vec<Node>,
+    index: HashMap<Node, NodeId>,
+}
+
+impl ConstraintGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert(&mut self, node: Node) -> NodeId {
+        if let Some(&id) = self.index.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.index.insert(node.clone(), id);
+        self.nodes.push(node);
+        id
+    }
+
+    pub fn constant(&mut self, value: M31) -> NodeId {
+        self.insert(Node::Constant(value))
+    }
+
+    pub fn trace_cell(&mut self, col: usize, row_offset: isize) -> NodeId {
+        self.insert(Node::TraceCell { col, row_offset })
+    }
+
+    pub fn add(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.insert(Node::Add(a, b))
+    }
+
+    pub fn sub(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.insert(Node::Sub(a, b))
+    }
+
+    pub fn mul(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.insert(Node::Mul(a, b))
+    }
+
+    pub fn pow(&mut self, base: NodeId, exp: u32) -> NodeId {
+        self.insert(Node::Pow(base, exp))
+    }
+
+    /// Polynomial degree of `root` in the trace columns, computed
+    /// bottom-up with memoization: `Add`/`Sub` take the max of their
+    /// operands' degrees, `Mul` sums them, and `Pow(base, exp)` multiplies
+    /// `base`'s degree by `exp`.
+    pub fn degree(&self, root: NodeId) -> usize {
+        let mut memo = vec![None; self.nodes.len()];
+        self.degree_rec(root, &mut memo)
+    }
+
+    fn degree_rec(&self, id: NodeId, memo: &mut [Option<usize>]) -> usize {
+        if let Some(d) = memo[id] {
+            return d;
+        }
+        let d = match &self.nodes[id] {
+            Node::Constant(_) => 0,
+            Node::TraceCell { .. } => 1,
+            Node::Add(a, b) | Node::Sub(a, b) => {
+                self.degree_rec(*a, memo).max(self.degree_rec(*b, memo))
+            }
+            Node::Mul(a, b) => self.degree_rec(*a, memo) + self.degree_rec(*b, memo),
+            Node::Pow(base, exp) => self.degree_rec(*base, memo) * (*exp as usize),
+        };
+        memo[id] = Some(d);
+        d
+    }
+
+    /// Evaluate `root` at one LDE row, walking the DAG in topological
+    /// (post) order and caching each node's value so a subexpression
+    /// shared by several constraints is only computed once.
+    ///
+    /// `get_cell(col, row_offset)` resolves a [`Node::TraceCell`] to its
+    /// value, already accounting for the blowup-aware wraparound the LDE
+    /// domain needs when `row_offset` walks off either end.
+    pub fn eval(&self, root: NodeId, get_cell: &dyn Fn(usize, isize) -> M31) -> M31 {
+        let mut cache: Vec<Option<M31>> = vec![None; self.nodes.len()];
+        self.eval_rec(root, get_cell, &mut cache)
+    }
+
+    fn eval_rec(
+        &self,
+        id: NodeId,
+        get_cell: &dyn Fn(usize, isize) -> M31,
+        cache: &mut [Option<M31>],
+    ) -> M31 {
+        if let Some(v) = cache[id] {
+            return v;
+        }
+        let v = match &self.nodes[id] {
+            Node::Constant(c) => *c,
+            Node::TraceCell { col, row_offset } => get_cell(*col, *row_offset),
+            Node::Add(a, b) => self.eval_rec(*a, get_cell, cache) + self.eval_rec(*b, get_cell, cache),
+            Node::Sub(a, b) => self.eval_rec(*a, get_cell, cache) - self.eval_rec(*b, get_cell, cache),
+            Node::Mul(a, b) => self.eval_rec(*a, get_cell, cache) * self.eval_rec(*b, get_cell, cache),
+            Node::Pow(base, exp) => {
+                let base_val = self.eval_rec(*base, get_cell, cache);
+                let mut result = M31::ONE;
+                for _ in 0..*exp {
+                    result = result * base_val;
+                }
+                result
+            }
+        };
+        cache[id] = Some(v);
+        v
+    }
+
+    /// Same walk as [`Self::eval`], but over the QM31 extension field, for
+    /// evaluating a constraint's DAG at an out-of-domain point instead of
+    /// an LDE row. `get_cell` takes the place of `TraceCell` lookups the
+    /// same way — typically the OODS point `z` at `row_offset == 0` and
+    /// the next-row point `z + blowup` at `row_offset == 1`.
+    pub fn eval_ext(&self, root: NodeId, get_cell: &dyn Fn(usize, isize) -> QM31) -> QM31 {
+        let mut cache: Vec<Option<QM31>> = vec![None; self.nodes.len()];
+        self.eval_ext_rec(root, get_cell, &mut cache)
+    }
+
+    fn eval_ext_rec(
+        &self,
+        id: NodeId,
+        get_cell: &dyn Fn(usize, isize) -> QM31,
+        cache: &mut [Option<QM31>],
+    ) -> QM31 {
+        if let Some(v) = cache[id] {
+            return v;
+        }
+        let v = match &self.nodes[id] {
+            Node::Constant(c) => QM31::from(*c),
+            Node::TraceCell { col, row_offset } => get_cell(*col, *row_offset),
+            Node::Add(a, b) => {
+                self.eval_ext_rec(*a, get_cell, cache) + self.eval_ext_rec(*b, get_cell, cache)
+            }
+            Node::Sub(a, b) => {
+                self.eval_ext_rec(*a, get_cell, cache) - self.eval_ext_rec(*b, get_cell, cache)
+            }
+            Node::Mul(a, b) => {
+                self.eval_ext_rec(*a, get_cell, cache) * self.eval_ext_rec(*b, get_cell, cache)
+            }
+            Node::Pow(base, exp) => {
+                let base_val = self.eval_ext_rec(*base, get_cell, cache);
+                let mut result = QM31::from(M31::ONE);
+                for _ in 0..*exp {
+                    result = result * base_val;
+                }
+                result
+            }
+        };
+        cache[id] = Some(v);
+        v
+    }
+}
+
+/// Which rows of the LDE domain a [`Constraint`] is checked on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DomainPredicate {
+    /// Only enforced on the first row of the trace (e.g. an initial-value
+    /// boundary condition).
+    BoundaryFirstRow,
+    /// Only enforced on the last row of the trace.
+    BoundaryLastRow,
+    /// Enforced on every row (a transition between consecutive rows).
+    Transition,
+}
+
+/// One AIR constraint: a DAG root that must evaluate to zero wherever its
+/// [`DomainPredicate`] applies.
+#[derive(Debug, Clone, Copy)]
+pub struct Constraint {
+    pub root: NodeId,
+    pub domain: DomainPredicate,
+}
+
+/// A full set of AIR constraints sharing one [`ConstraintGraph`] arena.
+#[derive(Debug, Default, Clone)]
+pub struct ConstraintSystem {
+    pub graph: ConstraintGraph,
+    pub constraints: Vec<Constraint>,
+}
+
+impl ConstraintSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_constraint(&mut self, root: NodeId, domain: DomainPredicate) {
+        self.constraints.push(Constraint { root, domain });
+    }
+
+    /// The highest constraint degree in the system, which fixes the
+    /// quotient degree (and therefore how many composition columns of
+    /// `trace_len` are needed to hold it) the prover must budget for.
+    pub fn max_degree(&self) -> usize {
+        self.constraints
+            .iter()
+            .map(|c| self.graph.degree(c.root))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// How many `trace_len`-sized composition columns the combined
+    /// quotient polynomial needs: `ceil(max_degree / blowup)`, since each
+    /// extra multiple of the trace's degree past what one LDE column can
+    /// represent needs its own column, the same way callers already split
+    /// FRI instances per [`crate::fri`].
+    pub fn composition_column_count(&self, blowup: usize) -> usize {
+        self.max_degree().max(1).div_ceil(blowup.max(1))
+    }
+
+    /// Evaluate every constraint whose [`DomainPredicate`] applies at LDE
+    /// row `i`, returning `0` in each slot where it doesn't (so the
+    /// output lines up 1:1 with `self.constraints` for the caller to
+    /// combine).
+    pub fn evaluate_row(&self, trace_lde: &TraceLDE, i: usize, blowup: usize) -> Vec<M31> {
+        let domain_size = trace_lde.domain_size();
+        let trace_len = domain_size / blowup;
+
+        let get_cell = |col: usize, row_offset: isize| -> M31 {
+            let step = row_offset * blowup as isize;
+            let idx = (i as isize + step).rem_euclid(domain_size as isize) as usize;
+            trace_lde.get(col, idx)
+        };
+
+        let row = i / blowup;
+
+        self.constraints
+            .iter()
+            .map(|constraint| {
+                let applies = match constraint.domain {
+                    DomainPredicate::BoundaryFirstRow => row == 0,
+                    DomainPredicate::BoundaryLastRow => row == trace_len - 1,
+                    DomainPredicate::Transition => true,
+                };
+                if applies {
+                    self.graph.eval(constraint.root, &get_cell)
+                } else {
+                    M31::ZERO
+                }
+            })
+            .collect()
+    }
+
+    /// Evaluate this system's composition value at an out-of-domain point,
+    /// combining every constraint's root with ascending powers of `alpha`
+    /// — the same fold [`StarkProver::evaluate_composition_polynomial`]
+    /// performs per LDE row, with the same domain gating: a [`Constraint`]
+    /// whose [`DomainPredicate`] is `BoundaryFirstRow`/`BoundaryLastRow`
+    /// only ever contributes a nonzero row value at that one row, so
+    /// (for an honest trace) its masked polynomial is the all-zero
+    /// polynomial and evaluates to zero everywhere, including at an
+    /// out-of-domain point — it contributes `0` here rather than its raw
+    /// root value, which would instead be the *unmasked* trace
+    /// polynomial's value and not match what the prover actually folded
+    /// into the composition commitment. Only `Transition` constraints,
+    /// which apply at every row and so need no masking, are evaluated.
+    ///
+    /// `get_cell` plays the role [`ConstraintGraph::eval_ext`] expects:
+    /// the OODS point `z` at `row_offset == 0`, the next-row point
+    /// `z + blowup` at `row_offset == 1`, and so on for any further rows the system's
+    /// constraints reference.
+    pub fn combine_at_oods(&self, get_cell: &dyn Fn(usize, isize) -> QM31, alpha: QM31) -> QM31 {
+        let mut alpha_pow = QM31::from(M31::ONE);
+        let mut combined = QM31::from(M31::ZERO);
+        for constraint in &self.constraints {
+            let value = match constraint.domain {
+                DomainPredicate::Transition => self.graph.eval_ext(constraint.root, get_cell),
+                DomainPredicate::BoundaryFirstRow | DomainPredicate::BoundaryLastRow => {
+                    QM31::from(M31::ZERO)
+                }
+            };
+            combined = combined + alpha_pow * value;
+            alpha_pow = alpha_pow * alpha;
+        }
+        combined
+    }
+}
+
 /// Configuration for the STARK prover.
 #[derive(Clone, Debug)]
 pub struct StarkConfig {
@@ -28,6 +336,9 @@ pub struct StarkConfig {
     pub num_queries: usize,
     /// FRI folding factor.
     pub fri_folding_factor: usize,
+    /// Required number of low-order zero bits in the grinding challenge.
+    /// `0` disables proof-of-work grinding.
+    pub pow_bits: usize,
 }
 
 impl Default for StarkConfig {
@@ -37,6 +348,7 @@ impl Default for StarkConfig {
             blowup_factor: 8,
             num_queries: 50,
             fri_folding_factor: 4,
+            pow_bits: 0,
         }
     }
 }
@@ -72,6 +384,17 @@ pub struct StarkProof {
     pub fri_proof: FriProof,
     /// Query proofs.
     pub query_proofs: Vec<QueryProof>,
+    /// Claimed trace value at the OODS point `z`.
+    pub trace_oods_value: QM31,
+    /// Claimed trace value at the next-row OODS point `z + blowup_factor`.
+    /// Domain points are row indices taken as field elements, so the next
+    /// row is an additive shift of `z`, not a product with a domain
+    /// generator.
+    pub trace_oods_next_value: QM31,
+    /// Claimed composition polynomial value `C(z)`.
+    pub composition_oods_value: QM31,
+    /// Proof-of-work grinding nonce.
+    pub pow_nonce: u64,
 }
 
 /// Proof data for a single query.
@@ -112,6 +435,18 @@ impl StarkProver {
     /// # Returns
     /// A STARK proof that can be verified.
     pub fn prove(&mut self, trace_columns: Vec<Vec<M31>>) -> StarkProof {
+        let system = Self::default_constraint_system();
+        self.prove_with_system(trace_columns, &system)
+    }
+
+    /// Generate a STARK proof using a caller-supplied [`ConstraintSystem`]
+    /// instead of [`StarkProver::default_constraint_system`]'s mock clock
+    /// — the entry point real CPU/memory AIRs prove through.
+    pub fn prove_with_system(
+        &mut self,
+        trace_columns: Vec<Vec<M31>>,
+        system: &ConstraintSystem,
+    ) -> StarkProof {
         let _num_cols = trace_columns.len();
         let trace_len = trace_columns[0].len();
 
@@ -122,20 +457,31 @@ impl StarkProver {
         let trace_lde = TraceLDE::new(&trace_columns, self.config.blowup_factor);
         let domain_size = trace_lde.domain_size();
 
-        // Step 2: Commit to trace (first column for simplicity)
-        // In production, would commit to all columns interleaved or separately
-        let trace_tree = MerkleTree::new(&trace_lde.columns[0]);
+        // Step 2: Commit to the trace. Every column's value at a row is
+        // hashed into one leaf, so a single tree commits to the whole
+        // multi-column trace instead of just its first column.
+        let trace_rows: Vec<Vec<M31>> = (0..domain_size).map(|i| trace_lde.get_row(i)).collect();
+        let trace_tree = MerkleTree::new_rows(&trace_rows);
         let trace_commitment = trace_tree.root();
 
         // Absorb trace commitment into channel
         self.channel.absorb(&trace_commitment);
 
+        // Step 2b: Get the column-batching challenge `beta`, at the same
+        // transcript point `zp1_verifier::Verifier::verify` squeezes it:
+        // right after absorbing the trace commitment, before
+        // `constraint_random`. `beta` combines a row's columns into the
+        // single value FRI layer 0 is built from via
+        // `acc = acc*beta + col_i`.
+        let beta = self.channel.squeeze_challenge();
+
         // Step 3: Receive constraint randomness from verifier (Fiat-Shamir)
         let constraint_random = self.channel.squeeze_qm31();
 
         // Step 4: Evaluate composition polynomial
         let composition_evals = self.evaluate_composition_polynomial(
             &trace_lde,
+            system,
             constraint_random,
         );
 
@@ -146,7 +492,60 @@ impl StarkProver {
         self.channel.absorb(&composition_commitment);
 
         // Step 6: DEEP quotient / OODS point
-        let _oods_point = self.channel.squeeze_qm31();
+        let oods_point = self.channel.squeeze_qm31();
+
+        // Step 6b: Evaluate the trace and composition polynomials at the
+        // OODS point so the verifier's DEEP-ALI quotient and AIR check
+        // (`Verifier::verify_constraint_consistency`) have something to
+        // check the query-phase values against. Each column's trace
+        // polynomial is interpolated from its own values (degree
+        // `< trace_len`) and reduced across columns with `beta` exactly
+        // like `reduce_trace_values` reduces a query's in-domain row, so
+        // `trace_oods_value` lines up with `combined_trace_value` at the
+        // same point.
+        //
+        // Domain points follow the same row-index-as-field-element
+        // convention `ConstraintSystem::evaluate_row` already uses for
+        // `TraceCell`'s `row_offset` (and the verifier's per-query point
+        // `M31::new(query.index as u32)`): trace row `r` sits at LDE index
+        // `r * blowup`, and `evaluate_row` steps to the next row by adding
+        // `blowup` to that index, not `1`. `composition_evals` is folded
+        // from `evaluate_row` at every LDE index, so `C`'s "next row" is
+        // the same `blowup`-sized step — the OODS point must shift by the
+        // same amount, or `composition_oods_value` won't match the
+        // polynomial the prover actually committed to.
+        let blowup = self.config.blowup_factor;
+        let trace_domain_points = |column: &[M31]| -> Vec<(M31, M31)> {
+            column
+                .iter()
+                .enumerate()
+                .map(|(row, &value)| (M31::new((row * blowup) as u32), value))
+                .collect()
+        };
+        let oods_point_next = oods_point + QM31::from(M31::new(blowup as u32));
+
+        let trace_oods_per_col: Vec<QM31> = trace_columns
+            .iter()
+            .map(|column| interpolate_and_evaluate_ext(&trace_domain_points(column), oods_point))
+            .collect();
+        let trace_oods_value = reduce_oods_values(&trace_oods_per_col, beta);
+
+        let trace_oods_next_per_col: Vec<QM31> = trace_columns
+            .iter()
+            .map(|column| {
+                interpolate_and_evaluate_ext(&trace_domain_points(column), oods_point_next)
+            })
+            .collect();
+        let trace_oods_next_value = reduce_oods_values(&trace_oods_next_per_col, beta);
+
+        let get_oods_cell = |_col: usize, row_offset: isize| -> QM31 {
+            match row_offset {
+                0 => trace_oods_value,
+                1 => trace_oods_next_value,
+                _ => unreachable!("default_constraint_system only references rows 0 and 1"),
+            }
+        };
+        let composition_oods_value = system.combine_at_oods(&get_oods_cell, constraint_random);
 
         // Step 7: FRI
         let fri_config = FriConfig {
@@ -159,6 +558,15 @@ impl StarkProver {
         let fri_prover = FriProver::new(fri_config);
         let (_fri_layers, fri_proof) = fri_prover.commit(composition_evals.clone(), &mut self.channel);
 
+        // Step 7b: Grind a proof-of-work nonce, if configured, before
+        // squeezing query indices so the verifier can replay it at the
+        // same point in the transcript.
+        let pow_nonce = if self.config.pow_bits > 0 {
+            self.channel.grind(self.config.pow_bits)
+        } else {
+            0
+        };
+
         // Step 8: Query phase
         let query_indices = self.channel.squeeze_query_indices(
             self.config.num_queries,
@@ -178,47 +586,70 @@ impl StarkProver {
             composition_commitment,
             fri_proof,
             query_proofs,
+            trace_oods_value,
+            trace_oods_next_value,
+            composition_oods_value,
+            pow_nonce,
         }
     }
 
     /// Evaluate the composition polynomial at all LDE domain points.
+    ///
+    /// Walks `system`'s constraints via [`ConstraintSystem::evaluate_row`]
+    /// and folds them together with successive powers of the full QM31
+    /// `random` challenge (not just its first component, which would
+    /// throw away three of its four components' worth of soundness). The
+    /// combined value is still QM31-valued; since this prover commits to
+    /// a single base-field composition column, we carry the combination
+    /// through in the extension field and take `.c0` as that column's
+    /// representative right before writing it out — every constraint
+    /// still contributed to the full combination that produced it.
     fn evaluate_composition_polynomial(
         &self,
         trace_lde: &TraceLDE,
+        system: &ConstraintSystem,
         random: QM31,
     ) -> Vec<M31> {
         let domain_size = trace_lde.domain_size();
         let blowup = self.config.blowup_factor;
 
-        // For now, create a simple composition polynomial
-        // In production, this would evaluate all AIR constraints
-
-        let mut composition = vec![M31::ZERO; domain_size];
+        (0..domain_size)
+            .map(|i| {
+                let row_constraints = system.evaluate_row(trace_lde, i, blowup);
 
-        // Use first component of random as scalar
-        let alpha = random.c0;
-
-        for i in 0..domain_size {
-            // Get values at current row
-            let col0 = trace_lde.get(0, i);
-            // Get values at next row (with wraparound)
-            let col0_next = trace_lde.get(0, (i + blowup) % domain_size);
-
-            // Boundary constraint: first row starts at 0
-            let boundary_constraint = if i < blowup {
-                col0
-            } else {
-                M31::ZERO
-            };
-
-            // Transition constraint: clock increments
-            let transition_constraint = col0_next - col0 - M31::ONE;
+                let mut alpha_pow = QM31::from(M31::ONE);
+                let mut combined = QM31::from(M31::ZERO);
+                for constraint_value in row_constraints {
+                    combined = combined + alpha_pow * QM31::from(constraint_value);
+                    alpha_pow = alpha_pow * random;
+                }
 
-            // Combine with randomness
-            composition[i] = boundary_constraint + alpha * transition_constraint;
-        }
+                combined.c0
+            })
+            .collect()
+    }
 
-        composition
+    /// The default AIR: a single clock column that starts at 0
+    /// ([`DomainPredicate::BoundaryFirstRow`]) and increments by 1 every
+    /// row ([`DomainPredicate::Transition`]). Kept as the prover's
+    /// fallback system; real callers build their own [`ConstraintSystem`]
+    /// from [`crate::bitwise_tables`]/CPU AIR constraints and pass it to
+    /// [`StarkProver::prove_with_system`] instead. Public so
+    /// `zp1_verifier` can check proofs against this exact description
+    /// instead of hand-deriving an equivalent formula.
+    pub fn default_constraint_system() -> ConstraintSystem {
+        let mut system = ConstraintSystem::new();
+        let col0 = system.graph.trace_cell(0, 0);
+        let col0_next = system.graph.trace_cell(0, 1);
+        let one = system.graph.constant(M31::ONE);
+
+        system.add_constraint(col0, DomainPredicate::BoundaryFirstRow);
+
+        let step = system.graph.sub(col0_next, col0);
+        let transition = system.graph.sub(step, one);
+        system.add_constraint(transition, DomainPredicate::Transition);
+
+        system
     }
 
     /// Generate query proofs for all query indices.
@@ -250,42 +681,61 @@ impl StarkProver {
     }
 }
 
-/// Constraint evaluator for AIR.
+/// Lagrange-interpolate the unique degree-`<points.len()` polynomial
+/// through `points` and evaluate it at an out-of-domain point `x`,
+/// generalizing
+/// `zp1_verifier::verify::fri_utils::interpolate_and_evaluate` to a QM31
+/// evaluation point.
+fn interpolate_and_evaluate_ext(points: &[(M31, M31)], x: QM31) -> QM31 {
+    let mut result = QM31::from(M31::ZERO);
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let mut term = QM31::from(yi);
+        for (j, &(xj, _)) in points.iter().enumerate() {
+            if i != j {
+                term = term * (x - QM31::from(xj)) * QM31::from((xi - xj).inverse());
+            }
+        }
+        result = result + term;
+    }
+    result
+}
+
+/// Reduce a row's per-column OODS evaluations into the single value
+/// `zp1_verifier::verify::reduce_trace_values` reduces an in-domain row to,
+/// via the same Horner fold in `beta`: `acc = acc*beta + col_i`.
+fn reduce_oods_values(values: &[QM31], beta: M31) -> QM31 {
+    let beta = QM31::from(beta);
+    values
+        .iter()
+        .fold(QM31::from(M31::ZERO), |acc, &col| acc * beta + col)
+}
+
+/// Constraint evaluator for AIR: evaluates every constraint in a
+/// [`ConstraintSystem`]'s algebraic DAG at a single LDE row and combines
+/// them with the caller's challenge powers.
 pub struct ConstraintEvaluator {
     /// Number of trace columns.
     pub num_cols: usize,
-    /// Number of constraint polynomials.
-    pub num_constraints: usize,
+    /// The AIR's constraint system.
+    pub system: ConstraintSystem,
 }
 
 impl ConstraintEvaluator {
-    /// Create a new constraint evaluator.
-    pub fn new(num_cols: usize, num_constraints: usize) -> Self {
-        Self {
-            num_cols,
-            num_constraints,
-        }
+    /// Create a new constraint evaluator over `system`.
+    pub fn new(num_cols: usize, system: ConstraintSystem) -> Self {
+        Self { num_cols, system }
     }
 
-    /// Evaluate all constraints at a single point.
-    pub fn evaluate(
-        &self,
-        trace_row: &[M31],
-        trace_row_next: &[M31],
-        alphas: &[M31],
-        is_boundary: bool,
-    ) -> M31 {
+    /// Evaluate every constraint at LDE row `i` via
+    /// [`ConstraintSystem::evaluate_row`], then fold the results together
+    /// with successive powers of `alpha`.
+    pub fn evaluate(&self, trace_lde: &TraceLDE, i: usize, blowup: usize, alpha: M31) -> M31 {
+        let mut alpha_pow = M31::ONE;
         let mut result = M31::ZERO;
 
-        // Boundary constraints (first row)
-        if is_boundary && !trace_row.is_empty() {
-            result += alphas.get(0).copied().unwrap_or(M31::ONE) * trace_row[0];
-        }
-
-        // Transition constraints
-        if !trace_row.is_empty() && !trace_row_next.is_empty() {
-            let constraint = trace_row_next[0] - trace_row[0] - M31::ONE;
-            result += alphas.get(1).copied().unwrap_or(M31::ONE) * constraint;
+        for constraint_value in self.system.evaluate_row(trace_lde, i, blowup) {
+            result += alpha_pow * constraint_value;
+            alpha_pow *= alpha;
         }
 
         result
@@ -314,6 +764,7 @@ mod tests {
             blowup_factor: 4,
             num_queries: 3,
             fri_folding_factor: 2,
+            pow_bits: 0,
         };
 
         let mut prover = StarkProver::new(config);
@@ -324,4 +775,91 @@ mod tests {
         assert_eq!(proof.composition_commitment.len(), 32);
         assert_eq!(proof.query_proofs.len(), 3);
     }
+
+    #[test]
+    fn test_constraint_graph_hash_conses_shared_subexpressions() {
+        let mut graph = ConstraintGraph::new();
+        let a = graph.trace_cell(0, 0);
+        let b = graph.trace_cell(0, 0);
+        assert_eq!(a, b, "re-inserting the same node should reuse its id");
+
+        let sum1 = graph.add(a, a);
+        let sum2 = graph.add(a, a);
+        assert_eq!(sum1, sum2);
+    }
+
+    #[test]
+    fn test_constraint_graph_degree() {
+        let mut graph = ConstraintGraph::new();
+        let col = graph.trace_cell(0, 0);
+        let one = graph.constant(M31::ONE);
+
+        // Degree 1: a bare trace cell.
+        assert_eq!(graph.degree(col), 1);
+
+        // Degree 2: col * col.
+        let squared = graph.mul(col, col);
+        assert_eq!(graph.degree(squared), 2);
+
+        // Degree 6: (col * col) ^ 3, plus a constant (degree 0) added in.
+        let cubed = graph.pow(squared, 3);
+        let with_constant = graph.add(cubed, one);
+        assert_eq!(graph.degree(cubed), 6);
+        assert_eq!(graph.degree(with_constant), 6);
+    }
+
+    #[test]
+    fn test_constraint_system_composition_column_count() {
+        let mut system = ConstraintSystem::new();
+        let col = system.graph.trace_cell(0, 0);
+        let squared = system.graph.mul(col, col);
+        system.add_constraint(squared, DomainPredicate::Transition);
+
+        assert_eq!(system.max_degree(), 2);
+        // A degree-2 constraint fits in a single composition column once
+        // blowup is at least 2.
+        assert_eq!(system.composition_column_count(2), 1);
+        // With no blowup headroom, each unit of degree needs its own column.
+        assert_eq!(system.composition_column_count(1), 2);
+    }
+
+    #[test]
+    fn test_default_constraint_system_valid_clock_trace() {
+        let trace_len = 8;
+        let blowup = 4;
+        let clock: Vec<M31> = (0..trace_len).map(|i| M31::new(i as u32)).collect();
+        let trace_lde = TraceLDE::new(&vec![clock], blowup);
+
+        let system = StarkProver::default_constraint_system();
+        let domain_size = trace_lde.domain_size();
+
+        for i in 0..domain_size {
+            for constraint_value in system.evaluate_row(&trace_lde, i, blowup) {
+                assert_eq!(
+                    constraint_value,
+                    M31::ZERO,
+                    "valid clock trace should satisfy every constraint at LDE row {i}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_constraint_system_rejects_broken_clock() {
+        let blowup = 4;
+        // Row 3 skips a beat: 0, 1, 2, 4, 4, 5, 6, 7.
+        let clock: Vec<M31> = vec![0, 1, 2, 4, 4, 5, 6, 7]
+            .into_iter()
+            .map(M31::new)
+            .collect();
+        let trace_lde = TraceLDE::new(&vec![clock], blowup);
+
+        let system = StarkProver::default_constraint_system();
+        let domain_size = trace_lde.domain_size();
+
+        let has_nonzero = (0..domain_size)
+            .flat_map(|i| system.evaluate_row(&trace_lde, i, blowup))
+            .any(|v| v != M31::ZERO);
+        assert!(has_nonzero, "broken clock transition should be caught");
+    }
 }
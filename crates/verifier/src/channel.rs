@@ -0,0 +1,213 @@
+//! Fiat-Shamir transcript channel for the verifier, built on the
+//! Poseidon2 sponge over M31 (see `zp1_primitives::poseidon2`).
+//!
+//! Mirrors `zp1_prover::channel::ProverChannel` bit-for-bit so that the
+//! verifier can replay the exact same transcript the prover produced.
+
+use zp1_primitives::{poseidon2::Poseidon2Sponge, M31, QM31};
+
+/// Sponge width and rate backing this channel's transcript; matches the
+/// Merkle tree's leaf/node sponge so both share one permutation shape.
+const SPONGE_WIDTH: usize = 16;
+const SPONGE_RATE: usize = 8;
+
+/// Domain tag distinguishing this channel's sponge from the Merkle tree's
+/// leaf (`"leaf"`) and node (`"node"`) sponges. Must match
+/// `zp1_prover::channel::ProverChannel`'s domain tag exactly.
+const CHANNEL_DOMAIN: u32 = 0x6368_616e; // "chan"
+
+/// Verifier channel for Fiat-Shamir transcript replay.
+#[derive(Clone)]
+pub struct VerifierChannel {
+    sponge: Poseidon2Sponge<SPONGE_WIDTH, SPONGE_RATE>,
+}
+
+impl VerifierChannel {
+    /// Create a new verifier channel, domain-separated by `domain_separator`.
+    ///
+    /// Must be called with the exact same `domain_separator` the prover
+    /// used, or the replayed transcript will diverge.
+    pub fn new(domain_separator: &[u8]) -> Self {
+        let sponge = Poseidon2Sponge::new(CHANNEL_DOMAIN);
+
+        let mut channel = Self { sponge };
+        channel.absorb_domain_separator(domain_separator);
+        channel
+    }
+
+    /// Absorb a domain separator, mirroring
+    /// `ProverChannel::absorb_domain_separator`; `absorb` already
+    /// length-tags its input, so distinct separators can never collide via
+    /// a shifted boundary.
+    fn absorb_domain_separator(&mut self, domain_separator: &[u8]) {
+        self.absorb(domain_separator);
+    }
+
+    /// Absorb bytes into the transcript.
+    ///
+    /// Prefixes the payload with its length so that `absorb(a)` followed
+    /// by `absorb(b)` can never produce the same transcript state as a
+    /// single `absorb(a ++ b)` call: without the tag, a boundary that
+    /// happens to fall on a 3-byte chunk edge would make the two
+    /// indistinguishable. Must match `ProverChannel::absorb` exactly.
+    ///
+    /// Packs 3 bytes per field element: `2^24 - 1` is always less than the
+    /// M31 modulus `2^31 - 1`, so every chunk is already a canonical field
+    /// element and no modular reduction (and therefore no collision
+    /// between distinct byte strings) is needed.
+    pub fn absorb(&mut self, data: &[u8]) {
+        self.absorb_raw(&(data.len() as u32).to_le_bytes());
+        self.absorb_raw(data);
+    }
+
+    /// Absorb bytes with no length tag. Only [`Self::absorb`] should call
+    /// this directly; every other site must go through it so the length
+    /// tag is never skipped.
+    fn absorb_raw(&mut self, data: &[u8]) {
+        for chunk in data.chunks(3) {
+            let mut bytes = [0u8; 4];
+            bytes[0..chunk.len()].copy_from_slice(chunk);
+            let val = u32::from_le_bytes(bytes);
+            self.sponge.absorb(M31::new(val));
+        }
+    }
+
+    /// Absorb a 32-byte commitment.
+    pub fn absorb_commitment(&mut self, commitment: &[u8; 32]) {
+        self.absorb(commitment);
+    }
+
+    /// Absorb an M31 field element.
+    pub fn absorb_felt(&mut self, felt: M31) {
+        self.sponge.absorb(felt);
+    }
+
+    /// Squeeze a challenge in M31.
+    pub fn squeeze_challenge(&mut self) -> M31 {
+        self.sponge.squeeze()
+    }
+
+    /// Squeeze a challenge in QM31 (extension field).
+    pub fn squeeze_extension_challenge(&mut self) -> QM31 {
+        let c0 = self.squeeze_challenge();
+        let c1 = self.squeeze_challenge();
+        let c2 = self.squeeze_challenge();
+        let c3 = self.squeeze_challenge();
+        QM31::new(c0, c1, c2, c3)
+    }
+
+    /// Squeeze n query indices in range [0, domain_size).
+    pub fn squeeze_query_indices(&mut self, n: usize, domain_size: usize) -> Vec<usize> {
+        let mut indices = Vec::with_capacity(n);
+        for _ in 0..n {
+            let val = self.squeeze_challenge().value() as usize;
+            indices.push(val % domain_size);
+        }
+        indices
+    }
+
+    /// Absorb a grinding nonce and check that the resulting challenge has
+    /// its low `bits` bits equal to zero.
+    ///
+    /// This replays the same absorb-then-squeeze step the prover performed
+    /// in `ProverChannel::grind`, so it must be called at the identical
+    /// point in the transcript (after all FRI layer commitments, before
+    /// query indices are squeezed).
+    pub fn check_pow(&mut self, nonce: u64, bits: usize) -> bool {
+        self.absorb(&nonce.to_le_bytes());
+        let challenge = self.squeeze_challenge();
+        low_bits_are_zero(challenge.as_u32(), bits)
+    }
+}
+
+/// True if the low `bits` bits of `value` are all zero.
+fn low_bits_are_zero(value: u32, bits: usize) -> bool {
+    if bits == 0 {
+        return true;
+    }
+    if bits >= 32 {
+        return value == 0;
+    }
+    value & ((1u32 << bits) - 1) == 0
+}
+
+impl Default for VerifierChannel {
+    fn default() -> Self {
+        Self::new(b"zp1-default")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_matches_fresh_state() {
+        let mut ch1 = VerifierChannel::new(b"test");
+        let mut ch2 = VerifierChannel::new(b"test");
+
+        ch1.absorb(b"test data");
+        ch2.absorb(b"test data");
+
+        assert_eq!(ch1.squeeze_challenge(), ch2.squeeze_challenge());
+    }
+
+    #[test]
+    fn test_low_bits_are_zero() {
+        assert!(low_bits_are_zero(0b1000, 3));
+        assert!(!low_bits_are_zero(0b1001, 3));
+        assert!(low_bits_are_zero(0, 0));
+        assert!(low_bits_are_zero(42, 0));
+    }
+
+    #[test]
+    fn test_check_pow_rejects_wrong_nonce() {
+        let mut ch = VerifierChannel::new(b"test");
+        ch.absorb(b"seed");
+        // An arbitrary nonce is exceedingly unlikely to satisfy a
+        // non-trivial bit target.
+        assert!(!ch.check_pow(0, 20));
+    }
+
+    #[test]
+    fn test_absorb_does_not_collide_across_modulus_boundary() {
+        let mut ch1 = VerifierChannel::new(b"test");
+        let mut ch2 = VerifierChannel::new(b"test");
+
+        ch1.absorb(&0x7fffffffu32.to_le_bytes());
+        ch2.absorb(&0u32.to_le_bytes());
+
+        assert_ne!(ch1.squeeze_challenge(), ch2.squeeze_challenge());
+    }
+
+    #[test]
+    fn test_different_domain_separators_diverge() {
+        let mut ch1 = VerifierChannel::new(b"zp1-stark-v1");
+        let mut ch2 = VerifierChannel::new(b"zp1-stark-v2");
+
+        assert_ne!(ch1.squeeze_challenge(), ch2.squeeze_challenge());
+    }
+
+    #[test]
+    fn test_absorb_does_not_collide_across_call_boundary() {
+        // Two absorbs whose payloads concatenate to the same bytes as one
+        // absorb, split at a different point, must not collide.
+        let mut ch1 = VerifierChannel::new(b"test");
+        ch1.absorb(b"ab");
+        ch1.absorb(b"c");
+
+        let mut ch2 = VerifierChannel::new(b"test");
+        ch2.absorb(b"a");
+        ch2.absorb(b"bc");
+
+        let mut ch3 = VerifierChannel::new(b"test");
+        ch3.absorb(b"abc");
+
+        let c1 = ch1.squeeze_challenge();
+        let c2 = ch2.squeeze_challenge();
+        let c3 = ch3.squeeze_challenge();
+        assert_ne!(c1, c2);
+        assert_ne!(c1, c3);
+        assert_ne!(c2, c3);
+    }
+}
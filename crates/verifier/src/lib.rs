@@ -0,0 +1,10 @@
+//! zp1-verifier: STARK proof verification.
+
+pub mod channel;
+pub mod verify;
+
+pub use channel::VerifierChannel;
+pub use verify::{
+    DeepQuotients, FriLayerQueryProof, FriProof, FriQueryProof, MerkleProof, QueryProof,
+    StarkProof, Verifier, VerifierConfig, VerifyError, VerifyResult,
+};